@@ -0,0 +1,197 @@
+//! Baselines, which record the problems of a codebase so they can be suppressed while new problems are still reported.
+
+use crate::{formatters::primary_label, ignore::normalize, FileWalker, JsFile};
+use hashbrown::HashMap;
+use rslint_core::{Diagnostic, LintResult};
+use rslint_lexer::Lexer;
+use serde::{Deserialize, Serialize};
+use std::env::current_dir;
+use std::fs::{read_to_string, write};
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// The problems found in a codebase when the baseline was written.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Baseline {
+    problems: Vec<BaselineProblem>,
+}
+
+/// A problem recorded in a baseline. Problems are not identified by their location, but by a fingerprint
+/// of the tokens on their lines, so they still match if lines are added or removed elsewhere in the file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+struct BaselineProblem {
+    /// The path of the file, relative to the working directory.
+    file: String,
+    rule: String,
+    message: String,
+    fingerprint: String,
+}
+
+impl Baseline {
+    /// Record the problems reported by rules and directives, syntax errors are never recorded.
+    pub fn new(results: &[LintResult], walker: &FileWalker) -> Self {
+        let mut problems = vec![];
+        for result in results {
+            let file = &walker.files[&result.file_id];
+            let tokens = tokens(&file.source);
+            for diagnostic in baselined_diagnostics(result) {
+                problems.push(BaselineProblem::new(file, &tokens, diagnostic));
+            }
+        }
+        // keep the order stable so baselines can be diffed
+        problems.sort();
+        Self { problems }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(serde_json::from_str(&read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write(path, serde_json::to_string_pretty(self)? + "\n")
+    }
+
+    /// The number of problems in the baseline.
+    pub fn len(&self) -> usize {
+        self.problems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Remove the diagnostics which are in the baseline, returning how many were removed.
+    /// A problem which is in the baseline once only suppresses one diagnostic.
+    pub fn suppress(&self, results: &mut [LintResult], walker: &FileWalker) -> usize {
+        let mut remaining = HashMap::new();
+        for problem in &self.problems {
+            *remaining.entry(problem).or_insert(0usize) += 1;
+        }
+
+        let mut suppressed = 0;
+        for result in results.iter_mut() {
+            let file = &walker.files[&result.file_id];
+            let tokens = tokens(&file.source);
+            let mut is_new = |diagnostic: &Diagnostic| {
+                let problem = BaselineProblem::new(file, &tokens, diagnostic);
+                match remaining.get_mut(&problem) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        suppressed += 1;
+                        false
+                    }
+                    _ => true,
+                }
+            };
+
+            for rule_result in result.rule_results.values_mut() {
+                rule_result
+                    .diagnostics
+                    .retain(|diagnostic| is_new(diagnostic));
+            }
+            result
+                .directive_diagnostics
+                .retain(|diagnostic| is_new(diagnostic));
+        }
+        suppressed
+    }
+}
+
+impl BaselineProblem {
+    fn new(file: &JsFile, tokens: &[Range<usize>], diagnostic: &Diagnostic) -> Self {
+        // virtual files are named by their path relative to the working directory
+        let path = file.path.as_ref().map_or_else(
+            || PathBuf::from(&file.name),
+            |path| {
+                let path = normalize(path);
+                path.strip_prefix(current_dir().unwrap_or_default())
+                    .map_or_else(|_| path.clone(), Path::to_owned)
+            },
+        );
+        let range = primary_label(diagnostic).map_or(0..0, |label| label.range.clone());
+
+        Self {
+            file: path.to_string_lossy().replace('\\', "/"),
+            rule: diagnostic.code.clone().unwrap_or_default(),
+            message: diagnostic.message.clone(),
+            fingerprint: fingerprint(&file.source, tokens, range),
+        }
+    }
+}
+
+fn baselined_diagnostics<'a>(result: &'a LintResult) -> impl Iterator<Item = &'a Diagnostic> {
+    result
+        .rule_results
+        .values()
+        .flat_map(|res| res.diagnostics.iter())
+        .chain(result.directive_diagnostics.iter())
+}
+
+/// The ranges of the tokens of a file which are not whitespace or comments.
+fn tokens(source: &str) -> Vec<Range<usize>> {
+    let mut tokens = vec![];
+    let mut offset = 0;
+    for (token, _) in Lexer::from_str(source, 0) {
+        if !token.kind.is_trivia() && token.len > 0 {
+            tokens.push(offset..offset + token.len);
+        }
+        offset += token.len;
+    }
+    tokens
+}
+
+/// A hash of the text of the tokens on the lines of a range, whitespace and comments are not part of the fingerprint.
+/// Tokens on other lines are not included so that changes to the lines around a problem do not change its fingerprint.
+fn fingerprint(source: &str, tokens: &[Range<usize>], range: Range<usize>) -> String {
+    let start = source[..range.start].rfind('\n').map_or(0, |idx| idx + 1);
+    let end = source[range.end..]
+        .find('\n')
+        .map_or(source.len(), |idx| range.end + idx);
+
+    let mut hash = Fnv::default();
+    for token in tokens
+        .iter()
+        .filter(|token| token.end > start && token.start < end)
+    {
+        hash.write(source[token.clone()].as_bytes());
+        hash.write(b" ");
+    }
+    format!("{:016x}", hash.0)
+}
+
+/// The 64 bit FNV-1a hash, which unlike the hashers of std is guaranteed to be the same on every platform and Rust version.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_ignore_other_lines() {
+        let source = "let a = 5;\nif (a) {}\n";
+        let shifted = "// a comment\nlet a = 5;\n\nif (a)   {}\nfoo();\n";
+
+        let print = |source: &str, range: Range<usize>| fingerprint(source, &tokens(source), range);
+        assert_eq!(print(source, 18..20), print(shifted, 34..36));
+        assert_ne!(
+            print(source, 18..20),
+            print("let b = 5;\nif (b) {}\n", 18..20)
+        );
+    }
+}
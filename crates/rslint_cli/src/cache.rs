@@ -0,0 +1,194 @@
+//! A cache of lint results which is kept between runs with `--cache`, so files which did not change are not linted again.
+
+use crate::{ignore::normalize, JsFile};
+use rslint_core::{CstRuleStore, Diagnostic, LintResult, RuleResult};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{read_to_string, write};
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The name of the cache file, which is written to the linting directory.
+pub const CACHE_FILE_NAME: &str = ".rslintcache";
+
+/// The results of linting each file, keyed by the absolute path of the file.
+///
+/// A result is only used if its file has the same content, config, and linter version as when it was cached.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Cache {
+    version: String,
+    files: BTreeMap<String, CacheEntry>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct CacheEntry {
+    /// The hash of the source of the file, its config, and the linter version.
+    hash: u64,
+    parser_diagnostics: Vec<Diagnostic>,
+    rule_results: HashMap<String, RuleResult>,
+    directive_diagnostics: Vec<Diagnostic>,
+}
+
+impl Cache {
+    /// Load a cache file, starting with an empty cache if the file does not exist, is invalid,
+    /// or was written by a different version of the linter.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let cache = read_to_string(path)
+            .ok()
+            .and_then(|source| serde_json::from_str::<Self>(&source).ok())
+            .filter(|cache| cache.version == env!("CARGO_PKG_VERSION"));
+
+        Self {
+            path: path.to_owned(),
+            ..cache.unwrap_or_else(|| Self {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                ..Self::default()
+            })
+        }
+    }
+
+    /// Write the cache back to the file it was loaded from.
+    pub fn save(&self) -> io::Result<()> {
+        write(&self.path, serde_json::to_string(self)?)
+    }
+
+    /// Get the cached result of a file linted with a store, `hash` is made by [`file_hash`].
+    ///
+    /// Results with fixes are not returned if `fix` is true, so the fixes can be applied.
+    pub fn get<'s>(
+        &self,
+        file: &JsFile,
+        hash: u64,
+        store: &'s CstRuleStore,
+        fix: bool,
+    ) -> Option<LintResult<'s>> {
+        let entry = self.files.get(&Self::key(file)?)?;
+        if entry.hash != hash
+            || (fix && entry.rule_results.values().any(|res| !res.fixes.is_empty()))
+        {
+            return None;
+        }
+
+        let mut rule_results = std::collections::HashMap::new();
+        for (name, result) in &entry.rule_results {
+            // the names of rules are static, so they have to be taken from the rules of the store
+            let name = store.rules.iter().find(|rule| rule.name() == name)?.name();
+            rule_results.insert(name, result.clone());
+        }
+
+        let mut result = LintResult {
+            file_id: file.id,
+            parser_diagnostics: entry.parser_diagnostics.clone(),
+            store,
+            rule_results,
+            directive_diagnostics: entry.directive_diagnostics.clone(),
+        };
+        // file ids are not stable between runs
+        let diagnostics = result
+            .parser_diagnostics
+            .iter_mut()
+            .chain(result.directive_diagnostics.iter_mut())
+            .chain(
+                result
+                    .rule_results
+                    .values_mut()
+                    .flat_map(|res| res.diagnostics.iter_mut()),
+            );
+        for diagnostic in diagnostics {
+            for label in diagnostic.labels.iter_mut() {
+                label.file_id = file.id;
+            }
+        }
+        Some(result)
+    }
+
+    /// Cache the result of linting a file, `hash` is made by [`file_hash`].
+    pub fn insert(&mut self, file: &JsFile, hash: u64, result: &LintResult) {
+        let key = match Self::key(file) {
+            Some(key) => key,
+            None => return,
+        };
+        let entry = CacheEntry {
+            hash,
+            parser_diagnostics: result.parser_diagnostics.clone(),
+            rule_results: result
+                .rule_results
+                .iter()
+                .map(|(name, res)| (name.to_string(), res.clone()))
+                .collect(),
+            directive_diagnostics: result.directive_diagnostics.clone(),
+        };
+        self.files.insert(key, entry);
+    }
+
+    /// Files are cached by their absolute path, virtual files are not cached.
+    fn key(file: &JsFile) -> Option<String> {
+        Some(
+            normalize(file.path.as_ref()?)
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+/// The hash of a config, which is part of the hash of every file linted with the config.
+pub fn config_hash(config: Option<&crate::config::Config>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    // configs only contain ordered maps so they are always serialized the same way
+    serde_json::to_string(&config)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The hash of a file linted with a config, `config_hash` is made by [`config_hash`].
+pub fn file_hash(source: &str, config_hash: u64, verbose: bool) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    config_hash.hash(&mut hasher);
+    verbose.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_core::{groups::errors::NoEmpty, lint_file};
+
+    #[test]
+    fn cached_results() {
+        let path = std::env::temp_dir().join("rslint-cache-test");
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![Box::new(NoEmpty::default()) as Box<_>]);
+
+        let file = JsFile::new_concrete("if (a) {}".to_string(), PathBuf::from("foo.js"));
+        let result = lint_file(file.id, &file.source, false, &store, false).unwrap();
+        let hash = file_hash(&file.source, config_hash(None), false);
+
+        let mut cache = Cache::load(&path);
+        cache.insert(&file, hash, &result);
+        cache.save().unwrap();
+
+        // the same file gets a new id in the next run
+        let file = JsFile::new_concrete(file.source, file.path.unwrap());
+        let cache = Cache::load(&path);
+        let cached = cache.get(&file, hash, &store, false).unwrap();
+        assert_eq!(cached.rule_results["no-empty"].diagnostics.len(), 1);
+        assert_eq!(
+            cached.rule_results["no-empty"].diagnostics[0].labels[0].file_id,
+            file.id
+        );
+
+        let changed = file_hash("if (b) {}", config_hash(None), false);
+        assert!(cache.get(&file, changed, &store, false).is_none());
+        assert!(cache
+            .get(&file, hash, &CstRuleStore::new(), false)
+            .is_none());
+    }
+}
@@ -1,10 +1,116 @@
 //! CLI options
 
-use crate::{lint_err, DOCS_LINK_BASE, REPO_LINK};
+use crate::{lint_err, GitRevision, OutputFormat, MODULE_SYSTEMS, REPO_LINK};
 use ansi_term::Color::{Green, White, RGB};
 use regex::{Captures, Regex};
+use rslint_core::globals::ENVIRONMENTS;
 use rslint_lexer::{ansi_term, color};
-use ureq::get;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "rslint",
+    about = "An extremely fast and configurable JavaScript linter"
+)]
+pub struct Options {
+    /// Whether to include potentially spammy details in rule diagnostics.
+    #[structopt(short, long)]
+    pub verbose: bool,
+    /// Automatically fix problems where possible and write the fixed files back.
+    #[structopt(long, conflicts_with = "stdin")]
+    pub fix: bool,
+    /// Same as `--fix`, but report problems as if they were fixed without writing any files.
+    #[structopt(long)]
+    pub fix_dry_run: bool,
+    /// The format to output the results in, one of `long` (the default), `compact` (or `unix`), `github`,
+    /// `json`, `sarif`, `checkstyle`, or `junit`.
+    #[structopt(long, default_value = "long", possible_values = &OutputFormat::NAMES, case_insensitive = true)]
+    pub format: OutputFormat,
+    /// Fail the run if there are more than this many warnings.
+    #[structopt(long, value_name = "N")]
+    pub max_warnings: Option<usize>,
+    /// Lint files even if they are ignored by `.rslintignore`, the config, `.gitignore`, or by default.
+    #[structopt(long)]
+    pub no_ignore: bool,
+    /// Read ignore patterns from this file instead of `.rslintignore`.
+    #[structopt(long, value_name = "PATH", parse(from_os_str))]
+    pub ignore_path: Option<PathBuf>,
+    /// Lint source read from stdin instead of files.
+    #[structopt(long)]
+    pub stdin: bool,
+    /// The path of the source read from stdin, which is used to find its config, to decide whether it is a module,
+    /// and in the output. The file does not have to exist.
+    #[structopt(long, value_name = "PATH", parse(from_os_str), requires = "stdin")]
+    pub stdin_filename: Option<PathBuf>,
+    /// Only lint files which changed since the last run with `--cache`, results are cached in `.rslintcache`.
+    #[structopt(long)]
+    pub cache: bool,
+    /// Only lint files which changed since a git revision such as `main` or `HEAD~1`, including uncommitted changes and untracked files.
+    #[structopt(long, value_name = "REV", conflicts_with_all = &["staged", "stdin"])]
+    pub changed_since: Option<String>,
+    /// Only lint files with changes staged for the next commit.
+    #[structopt(long, conflicts_with = "stdin")]
+    pub staged: bool,
+    /// Only report problems on lines which changed, this requires `--changed-since` or `--staged`.
+    #[structopt(long)]
+    pub changed_lines: bool,
+    /// Keep running and lint files again when they change, configs are loaded again when they change.
+    #[structopt(long, conflicts_with = "stdin")]
+    pub watch: bool,
+    /// Record every current problem in a baseline file instead of reporting them, see `--baseline`.
+    #[structopt(long, value_name = "PATH", parse(from_os_str), conflicts_with_all = &["baseline", "watch"])]
+    pub write_baseline: Option<PathBuf>,
+    /// Do not report problems which are recorded in a baseline file written by `--write-baseline`.
+    #[structopt(long, value_name = "PATH", parse(from_os_str))]
+    pub baseline: Option<PathBuf>,
+    /// Print the config a file is linted with after merging every config and override which applies to it,
+    /// as TOML or as JSON with `--format json`, instead of linting.
+    #[structopt(long, value_name = "PATH", parse(from_os_str))]
+    pub print_config: Option<PathBuf>,
+    /// A glob pattern to lint.
+    #[structopt(default_value = "./")]
+    pub files: String,
+    #[structopt(subcommand)]
+    pub cmd: Option<SubCommand>,
+}
+
+impl Options {
+    /// The revision to find changed files with, if only changed files should be linted.
+    pub fn git_revision(&self) -> Option<GitRevision> {
+        if self.staged {
+            Some(GitRevision::Staged)
+        } else {
+            self.changed_since.clone().map(GitRevision::Since)
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub enum SubCommand {
+    /// Explain a list of rules, ex: `explain getter-return, no-cond-assign`
+    Explain { rules: Vec<String> },
+    /// Write a starter `rslintrc.toml` which lists every builtin rule with its default config
+    Init {
+        /// An environment the code runs in, e.g. `browser` or `node`, this can be given multiple times.
+        #[structopt(long, possible_values = &ENVIRONMENTS)]
+        env: Vec<String>,
+        /// The module system the code uses.
+        #[structopt(long, possible_values = &MODULE_SYSTEMS)]
+        modules: Option<String>,
+        /// Overwrite an existing `rslintrc.toml`.
+        #[structopt(long)]
+        force: bool,
+    },
+    /// List every builtin rule and how it is configured by the config of the current directory
+    Rules {
+        /// Print the rules as JSON.
+        #[structopt(long)]
+        json: bool,
+    },
+    /// Print a JSON Schema of `rslintrc.toml` for editors to complete and validate configs with
+    Schema,
+}
 
 /// A structure for converting user facing markdown docs to ANSI colored terminal explanations.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -14,27 +120,34 @@ pub struct ExplanationRunner {
 }
 
 impl ExplanationRunner {
-    /// Make a new runner and try to fetch the remote docs files for each rule.
+    /// Make a new runner with the docs of each rule, which are embedded in the linter.
     /// This automatically issues any linter errors for invalid rules.
-    pub fn new(mut rules: Vec<String>) -> Self {
-        let rule_names = rules.clone();
-        rules = rules
+    pub fn new(rules: Vec<String>) -> Self {
+        let mut rule_names = vec![];
+        let rules = rules
             .into_iter()
             .filter_map(|rule| {
-                let res = fetch_doc_file(&rule);
-                if res.is_none() {
+                let docs = rslint_core::get_rule_by_name(&rule)
+                    .map(|resolved| resolved.docs())
+                    .filter(|docs| !docs.is_empty());
+                if docs.is_none() {
                     lint_err!("Invalid rule: {}", rule);
+                } else {
+                    rule_names.push(rule);
                 }
-                res
+                docs.map(str::to_string)
             })
             .collect();
 
         Self { rules, rule_names }
     }
 
+    /// Remove the comment at the start of generated docs files.
     pub fn strip_rule_preludes(&mut self) {
         for rule in self.rules.iter_mut() {
-            rule.replace_range(0..70, "");
+            if let Some(idx) = rule.find("-->").filter(|_| rule.starts_with("<!--")) {
+                rule.replace_range(..idx + 3, "");
+            }
         }
     }
 
@@ -60,17 +173,35 @@ impl ExplanationRunner {
         }
     }
 
-    pub fn strip_config_or_extra_examples(&mut self) {
+    /// Remove the collapsed extra examples and the link to the source of the rule.
+    pub fn strip_extra_examples(&mut self) {
         for rule in self.rules.iter_mut() {
-            if let Some(idx) = rule.find("# Config") {
-                rule.truncate(idx - 1);
-            }
-            if let Some(idx) = rule.find("<details>") {
-                rule.truncate(idx - 1);
+            if let Some(idx) = rule.find("<details>").or_else(|| rule.find("[Source]")) {
+                rule.truncate(idx);
             }
         }
     }
 
+    /// Turn the table of config fields into a line for each field with its type and description.
+    pub fn replace_config_tables(&mut self) {
+        let regex = Regex::new(r"(?m)^\| `(.+?)` \| (.+?) \| (.*) \|$").unwrap();
+        let header = Regex::new(r"(?m)^\| Name \| Type \| Description \|\n\|[- |]*\|\n").unwrap();
+        for rule in self.rules.iter_mut() {
+            *rule = header.replace_all(rule, "").to_string();
+            *rule = regex
+                .replace_all(rule, |cap: &Captures| {
+                    let description = cap[3].trim().replace("<br>", " ");
+                    format!(
+                        "- `{}` ({}): {}",
+                        &cap[1],
+                        cap[2].trim_matches('`'),
+                        description
+                    )
+                })
+                .to_string();
+        }
+    }
+
     pub fn replace_inline_code_blocks(&mut self) {
         let regex = Regex::new("`(.+?)`").unwrap();
         for rule in self.rules.iter_mut() {
@@ -90,14 +221,15 @@ impl ExplanationRunner {
     pub fn append_link_to_docs(&mut self) {
         for (docs, name) in self.rules.iter_mut().zip(self.rule_names.iter()) {
             let group = rslint_core::get_rule_by_name(&name).unwrap().group();
-            let link = format!("{}/docs/rules/{}/{}.md", REPO_LINK, group, name);
+            let link = format!("{}/blob/master/docs/rules/{}/{}.md", REPO_LINK, group, name);
             docs.push_str(&format!("{}: {}\n", Green.paint("Docs").to_string(), link));
         }
     }
 
     pub fn render(&mut self) {
         self.strip_rule_preludes();
-        self.strip_config_or_extra_examples();
+        self.strip_extra_examples();
+        self.replace_config_tables();
         self.replace_headers();
         self.replace_code_blocks();
         self.replace_inline_code_blocks();
@@ -112,22 +244,3 @@ impl ExplanationRunner {
         }
     }
 }
-
-/// Try to resolve a rule name, then fetch its remote docs file.
-///
-/// # Panics
-/// Panics if the remote docs file cant be fetched for some reason.
-fn fetch_doc_file(rule: &str) -> Option<String> {
-    let resolved_rule = rslint_core::get_rule_by_name(rule)?;
-    Some(
-        get(&format!(
-            "{}/{}/{}.md",
-            DOCS_LINK_BASE,
-            resolved_rule.group(),
-            rule
-        ))
-        .call()
-        .into_string()
-        .expect("Failed to fetch remote rule docs file"),
-    )
-}
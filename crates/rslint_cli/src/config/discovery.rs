@@ -0,0 +1,381 @@
+//! Discovery of the configs which apply to a file.
+
+use super::{Config, ConfigError, CONFIG_NAME};
+use crate::ignore::normalize;
+use hashbrown::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Identifies the config files and the overrides which apply to a file,
+/// files with the same key have the same config.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConfigKey {
+    /// The paths of the config files, from the outermost to the nearest.
+    configs: Vec<PathBuf>,
+    /// The overrides which apply, as indices of a config in `configs` and an override in that config.
+    overrides: Vec<(usize, usize)>,
+}
+
+/// Finds the config of files by walking up from their directory.
+///
+/// The configs of the directory of a file and of each parent directory are merged, stopping at the
+/// first config with `root = true`. More specific configs take precedence, and the overrides of each
+/// config which match the file are applied right after the config itself.
+/// Config files are only loaded once.
+#[derive(Debug, Default)]
+pub struct ConfigResolver {
+    /// The config of each directory which was searched, `None` if the directory has no config.
+    dirs: HashMap<PathBuf, Option<Arc<Config>>>,
+}
+
+impl ConfigResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The configs which apply to the files of a directory, from the outermost to the nearest one.
+    pub fn chain(&mut self, dir: impl AsRef<Path>) -> Result<Vec<Arc<Config>>, ConfigError> {
+        let mut chain = vec![];
+        for ancestor in normalize(dir.as_ref()).ancestors() {
+            if let Some(config) = self.load(ancestor)? {
+                let root = config.root;
+                chain.push(config);
+                if root {
+                    break;
+                }
+            }
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Get the key of the config of a file, which can be turned into a config with [`ConfigResolver::config`].
+    pub fn key(&mut self, file: impl AsRef<Path>) -> Result<ConfigKey, ConfigError> {
+        let file = normalize(file.as_ref());
+        let chain = self.chain(file.parent().unwrap_or(&file))?;
+
+        let mut overrides = vec![];
+        for (config_idx, config) in chain.iter().enumerate() {
+            for (idx, override_) in config.overrides.iter().enumerate() {
+                if override_.matches(&file) {
+                    overrides.push((config_idx, idx));
+                }
+            }
+        }
+
+        Ok(ConfigKey {
+            configs: chain
+                .iter()
+                .filter_map(|config| config.path.clone())
+                .collect(),
+            overrides,
+        })
+    }
+
+    /// Merge the configs and overrides of a key, returns `None` if no config applies.
+    ///
+    /// # Panics
+    /// Panics if the key was not made by this resolver.
+    pub fn config(&self, key: &ConfigKey) -> Option<Config> {
+        let mut merged: Option<Config> = None;
+
+        for (config_idx, path) in key.configs.iter().enumerate() {
+            let config = self.dirs[path.parent().unwrap()]
+                .as_ref()
+                .expect("config key was not made by this resolver");
+
+            let mut config = Config::clone(config);
+            let overrides = std::mem::take(&mut config.overrides);
+            match merged.as_mut() {
+                Some(merged) => merged.merge(config),
+                None => merged = Some(config),
+            }
+
+            for (_, idx) in key.overrides.iter().filter(|(idx, _)| *idx == config_idx) {
+                merged.as_mut().unwrap().merge(overrides[*idx].config());
+            }
+        }
+        merged
+    }
+
+    /// Merge the configs which apply to the files of a directory, without any overrides.
+    /// Returns `None` if no config applies.
+    pub fn dir_config(&mut self, dir: impl AsRef<Path>) -> Result<Option<Config>, ConfigError> {
+        let mut merged: Option<Config> = None;
+        for config in self.chain(dir)? {
+            let mut config = Config::clone(&config);
+            config.overrides.clear();
+            match merged.as_mut() {
+                Some(merged) => merged.merge(config),
+                None => merged = Some(config),
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Resolve the merged config of a file, returns `None` if no config applies.
+    pub fn resolve(&mut self, file: impl AsRef<Path>) -> Result<Option<Config>, ConfigError> {
+        let key = self.key(file)?;
+        Ok(self.config(&key))
+    }
+
+    fn load(&mut self, dir: &Path) -> Result<Option<Arc<Config>>, ConfigError> {
+        if let Some(config) = self.dirs.get(dir) {
+            return Ok(config.clone());
+        }
+
+        let config = Config::from_file(dir.join(CONFIG_NAME))
+            .transpose()?
+            .map(Arc::new);
+        self.dirs.insert(dir.to_owned(), config.clone());
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_core::RuleLevel;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    /// Make a directory tree in the temp directory with some files, returning its path.
+    fn tree(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("rslint-config-{}", name));
+        let _ = remove_dir_all(&root);
+        for (path, content) in files {
+            let path = root.join(path);
+            create_dir_all(path.parent().unwrap()).unwrap();
+            write(path, content).unwrap();
+        }
+        root
+    }
+
+    fn level(config: &Config, rule: &str) -> Option<RuleLevel> {
+        let store = config.store();
+        store.get(rule)?;
+        Some(store.level(rule))
+    }
+
+    #[test]
+    fn merges_parent_configs() {
+        let root = tree(
+            "merge",
+            &[
+                (
+                    "rslintrc.toml",
+                    "[rules]\nallowed = [\"no-empty\"]\n[rules.errors]\nno-debugger = {}\nno-await-in-loop = {}\n[env]\nnode = true",
+                ),
+                (
+                    "packages/a/rslintrc.toml",
+                    "[rules]\nallowed = [\"no-debugger\"]\n[rules.warnings]\nno-empty = {}\n[env]\nbrowser = true",
+                ),
+            ],
+        );
+
+        let mut resolver = ConfigResolver::new();
+        let config = resolver
+            .resolve(root.join("packages/a/src/index.js"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(level(&config, "no-debugger"), None);
+        assert_eq!(level(&config, "no-empty"), Some(RuleLevel::Warning));
+        assert_eq!(level(&config, "no-await-in-loop"), Some(RuleLevel::Error));
+        assert!(config.globals().is_declared("window"));
+        assert!(config.globals().is_declared("process"));
+        assert_eq!(config.path, Some(root.join("packages/a/rslintrc.toml")));
+
+        let config = resolver.resolve(root.join("index.js")).unwrap().unwrap();
+        assert_eq!(level(&config, "no-debugger"), Some(RuleLevel::Error));
+        assert_eq!(level(&config, "no-empty"), None);
+    }
+
+    #[test]
+    fn merges_rule_levels() {
+        let root = tree(
+            "levels",
+            &[
+                (
+                    "rslintrc.toml",
+                    "[rules]\nallowed = [\"no-extra-semi\"]\n[rules.errors]\nno-await-in-loop = {}\n[rules.levels]\nno-empty = \"info\"\nno-debugger = \"off\"",
+                ),
+                (
+                    "a/rslintrc.toml",
+                    "[rules.warnings]\nno-empty = {}\n[rules.levels]\nnoDebugger = \"warn\"\nno-extra-semi = \"error\"\nno-await-in-loop = \"info\"",
+                ),
+            ],
+        );
+
+        let mut resolver = ConfigResolver::new();
+        let config = resolver.resolve(root.join("index.js")).unwrap().unwrap();
+        // rules which are only given a level are run with their default config
+        assert_eq!(level(&config, "no-empty"), Some(RuleLevel::Info));
+        assert_eq!(level(&config, "no-debugger"), None);
+        assert_eq!(level(&config, "no-extra-semi"), None);
+        assert_eq!(level(&config, "no-await-in-loop"), Some(RuleLevel::Error));
+
+        let config = resolver.resolve(root.join("a/index.js")).unwrap().unwrap();
+        assert_eq!(level(&config, "no-empty"), Some(RuleLevel::Warning));
+        assert_eq!(level(&config, "no-debugger"), Some(RuleLevel::Warning));
+        assert_eq!(level(&config, "no-extra-semi"), Some(RuleLevel::Error));
+        assert_eq!(level(&config, "no-await-in-loop"), Some(RuleLevel::Info));
+    }
+
+    #[test]
+    fn stops_at_root() {
+        let root = tree(
+            "root",
+            &[
+                ("rslintrc.toml", "[env]\nnode = true"),
+                ("a/rslintrc.toml", "root = true\n[env]\nbrowser = true"),
+            ],
+        );
+
+        let mut resolver = ConfigResolver::new();
+        assert_eq!(resolver.chain(root.join("a/b")).unwrap().len(), 1);
+        let globals = resolver
+            .resolve(root.join("a/b/c.js"))
+            .unwrap()
+            .unwrap()
+            .globals();
+        assert!(globals.is_declared("window"));
+        assert!(!globals.is_declared("process"));
+    }
+
+    #[test]
+    fn applies_overrides() {
+        let root = tree(
+            "overrides",
+            &[
+                (
+                    "rslintrc.toml",
+                    "
+                    [rules.errors]
+                    no-empty = {}
+
+                    [[overrides]]
+                    files = [\"tests/**\", \"*.test.js\"]
+                    env = { jest = true }
+                    [overrides.rules.warnings]
+                    no-empty = {}
+                    ",
+                ),
+                (
+                    "packages/a/rslintrc.toml",
+                    "[rules.errors]\nno-debugger = {}",
+                ),
+            ],
+        );
+
+        let mut resolver = ConfigResolver::new();
+        let test_key = resolver.key(root.join("tests/unit/a.js")).unwrap();
+        assert_eq!(
+            test_key,
+            resolver.key(root.join("tests/b.js")).unwrap(),
+            "files with the same configs and overrides should share a key"
+        );
+
+        let config = resolver.config(&test_key).unwrap();
+        assert_eq!(level(&config, "no-empty"), Some(RuleLevel::Warning));
+        assert!(config.globals().is_declared("describe"));
+
+        let config = resolver
+            .resolve(root.join("packages/a/src/foo.test.js"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(level(&config, "no-empty"), Some(RuleLevel::Warning));
+        assert_eq!(level(&config, "no-debugger"), Some(RuleLevel::Error));
+
+        let config = resolver
+            .resolve(root.join("src/tests.js"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(level(&config, "no-empty"), Some(RuleLevel::Error));
+        assert!(!config.globals().is_declared("describe"));
+
+        let config = resolver.dir_config(root.join("tests")).unwrap().unwrap();
+        assert_eq!(level(&config, "no-empty"), Some(RuleLevel::Error));
+    }
+
+    #[test]
+    fn extends_configs() {
+        let root = tree(
+            "extends",
+            &[
+                (
+                    "shared/base.toml",
+                    "
+                    extends = [\"rslint:recommended\"]
+                    root = true
+
+                    [rules]
+                    allowed = [\"no-debugger\"]
+                    [rules.warnings]
+                    no-empty = {}
+
+                    [[overrides]]
+                    files = [\"tests/\"]
+                    env = { jest = true }
+                    ",
+                ),
+                (
+                    "a/rslintrc.toml",
+                    "extends = [\"../shared/base.toml\"]\n[rules.errors]\nno-empty = {}",
+                ),
+            ],
+        );
+
+        let mut resolver = ConfigResolver::new();
+        let config = resolver.resolve(root.join("a/b.js")).unwrap().unwrap();
+        assert!(config.extends.is_empty());
+        assert!(!config.root);
+        assert_eq!(level(&config, "no-empty"), Some(RuleLevel::Error));
+        assert_eq!(level(&config, "no-debugger"), None);
+        assert_eq!(level(&config, "no-extra-semi"), Some(RuleLevel::Error));
+        // only the rules marked as recommended are enabled by the preset
+        assert_eq!(level(&config, "no-await-in-loop"), None);
+        assert!(!config.globals().is_declared("describe"));
+
+        // the overrides of extended configs are relative to the extending config
+        let config = resolver
+            .resolve(root.join("a/tests/b.js"))
+            .unwrap()
+            .unwrap();
+        assert!(config.globals().is_declared("describe"));
+    }
+
+    #[test]
+    fn reports_invalid_extends() {
+        let root = tree(
+            "invalid-extends",
+            &[
+                ("a/rslintrc.toml", "extends = [\"rslint:none\"]"),
+                ("b/rslintrc.toml", "extends = [\"./missing.toml\"]"),
+                ("c/rslintrc.toml", "extends = [\"../c/other.toml\"]"),
+                ("c/other.toml", "extends = [\"./rslintrc.toml\"]"),
+            ],
+        );
+
+        let mut resolver = ConfigResolver::new();
+        for dir in &["a", "b", "c"] {
+            let err = resolver.resolve(root.join(dir).join("d.js")).unwrap_err();
+            assert!(err.path.starts_with(root.join(dir)));
+        }
+        let err = resolver.resolve(root.join("c/d.js")).unwrap_err();
+        assert_eq!(err.path, root.join("c/other.toml"));
+
+        // errors point at the entry of `extends`
+        let err = resolver.resolve(root.join("a/d.js")).unwrap_err();
+        assert_eq!(&err.source[err.span.unwrap()], "\"rslint:none\"");
+    }
+
+    #[test]
+    fn reports_invalid_configs() {
+        let root = tree("invalid", &[("a/rslintrc.toml", "root = 5")]);
+        let err = ConfigResolver::new()
+            .resolve(root.join("a/b.js"))
+            .unwrap_err();
+        assert_eq!(err.path, root.join("a/rslintrc.toml"));
+        assert_eq!(&err.source[err.span.unwrap()], "5");
+    }
+}
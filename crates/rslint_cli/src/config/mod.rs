@@ -0,0 +1,709 @@
+//! Configuration file support.
+//!
+//! Every directory can have its own config, the config of a file is the result of merging the configs
+//! of its directory and every parent directory, see [`ConfigResolver`].
+
+mod discovery;
+mod validate;
+
+pub use self::discovery::*;
+
+use crate::{ignore::normalize, lint_warn, IgnorePatterns};
+use heck::{CamelCase, KebabCase};
+use rslint_core::{
+    get_group_rules_by_name, get_rule_by_name, get_rule_suggestion,
+    globals::{GlobalKind, Globals, DEFAULT_ENVIRONMENTS},
+    CstRule, CstRuleStore, RuleLevel, GROUP_NAMES,
+};
+use serde::de::{
+    value::MapAccessDeserializer, DeserializeSeed, Error, IntoDeserializer, MapAccess, Visitor,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::read_to_string;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml::from_str;
+use validate::{validate, Node, Problem};
+
+/// The name of the config file to search for.
+pub const CONFIG_NAME: &str = "rslintrc.toml";
+
+/// The prefix of the names of the builtin configs which can be extended, e.g. `rslint:recommended`.
+pub const PRESET_PREFIX: &str = "rslint:";
+
+/// The names of the builtin configs, without [`PRESET_PREFIX`].
+pub const PRESET_NAMES: [&str; 2] = ["recommended", "all"];
+
+/// An error which occurred while loading a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// The path of the invalid config file.
+    pub path: PathBuf,
+    /// The source of the invalid config file.
+    pub source: String,
+    pub message: String,
+    /// The range of the offending key or value in the source, if it is known.
+    pub span: Option<Range<usize>>,
+    /// A help message, such as the name of a rule with a similar name.
+    pub help: Option<String>,
+}
+
+impl ConfigError {
+    fn new(path: &Path, source: &str, message: String, span: Option<Range<usize>>) -> Self {
+        Self {
+            path: path.to_owned(),
+            source: source.to_string(),
+            message,
+            span,
+            help: None,
+        }
+    }
+
+    fn from_problem(path: &Path, source: &str, problem: Problem) -> Self {
+        Self {
+            help: problem.help,
+            ..Self::new(path, source, problem.message, Some(problem.span))
+        }
+    }
+
+    /// Point a syntax or deserialization error at the key or value at its position.
+    fn from_toml(path: &Path, source: &str, tree: Option<&Node>, error: toml::de::Error) -> Self {
+        let mut message = error.to_string();
+        let (line, col) = match error.line_col() {
+            Some(line_col) => line_col,
+            None => return Self::new(path, source, message, None),
+        };
+        // the position is part of the message, the diagnostic already shows it
+        let position = format!(" at line {} column {}", line + 1, col + 1);
+        if message.ends_with(&position) {
+            message.truncate(message.len() - position.len());
+        }
+
+        let offset = source
+            .split_inclusive('\n')
+            .take(line)
+            .map(str::len)
+            .sum::<usize>()
+            + col;
+        let span = tree
+            .and_then(|tree| tree.span_at(offset))
+            .unwrap_or_else(|| {
+                let end = source[offset.min(source.len())..]
+                    .find(char::is_whitespace)
+                    .map_or(source.len(), |len| offset + len.max(1));
+                offset.min(source.len())..end
+            });
+        Self::new(path, source, message, Some(span))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Config {
+    /// Whether to stop looking for configs in parent directories.
+    #[serde(default)]
+    pub root: bool,
+    /// Configs to extend, either paths relative to this config or builtin presets such as `rslint:recommended`.
+    ///
+    /// Configs are extended when they are loaded, so this is empty for loaded configs.
+    #[serde(default)]
+    pub extends: Vec<String>,
+    pub rules: Option<RulesConfig>,
+    /// Environments to enable or disable, e.g. `browser = true`.
+    #[serde(default)]
+    pub env: BTreeMap<String, bool>,
+    /// Extra globals and how they may be used, e.g. `jQuery = "readonly"`.
+    #[serde(default)]
+    pub globals: BTreeMap<String, GlobalKind>,
+    /// Patterns of files to ignore in gitignore syntax, relative to the config.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Whether to also ignore the files ignored by the `.gitignore` of the linting directory.
+    pub gitignore: Option<bool>,
+    /// The extensions of files to lint, `["js", "mjs"]` by default.
+    pub extensions: Option<Vec<String>>,
+    /// The module system of the linted files, with `esm` every file but `.cjs` files is parsed as a module.
+    pub modules: Option<ModuleSystem>,
+    /// Configuration which only applies to some files.
+    #[serde(default)]
+    pub overrides: Vec<Override>,
+    /// The file this config was loaded from, or the most specific file if this config was merged.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+/// The module system of the linted files, declared with `modules`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModuleSystem {
+    /// Files other than `.cjs` files are modules.
+    Esm,
+    /// Only `.mjs` files are modules, which is the default.
+    Commonjs,
+}
+
+/// Configuration which only applies to the files matching some patterns, declared with `[[overrides]]`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Override {
+    /// The patterns of files this override applies to, in gitignore syntax relative to the config.
+    pub files: Vec<String>,
+    pub rules: Option<RulesConfig>,
+    #[serde(default)]
+    pub env: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub globals: BTreeMap<String, GlobalKind>,
+    #[serde(skip)]
+    patterns: Option<IgnorePatterns>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct RulesConfig {
+    #[serde(deserialize_with = "from_rule_objects")]
+    errors: Vec<Box<dyn CstRule>>,
+
+    #[serde(deserialize_with = "from_rule_objects")]
+    warnings: Vec<Box<dyn CstRule>>,
+
+    groups: Vec<String>,
+    allowed: Vec<String>,
+
+    /// The level of rules by name, which takes precedence over the table the rule is configured in.
+    #[serde(deserialize_with = "from_rule_levels")]
+    levels: BTreeMap<String, RuleLevel>,
+}
+
+impl Config {
+    /// Load a config file and the configs it extends, return None if the file does not exist or if its unreadable.
+    pub fn from_file(path: impl AsRef<Path>) -> Option<Result<Self, ConfigError>> {
+        let path = path.as_ref();
+        let mut config = match Self::load(path, &mut vec![])? {
+            Ok(config) => config,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let dir = path.parent().unwrap_or(path);
+        for override_ in config.overrides.iter_mut() {
+            override_.patterns = Some(IgnorePatterns::new(
+                dir,
+                override_.files.iter().map(|pattern| pattern.as_str()),
+            ));
+        }
+        config.path = Some(path.to_owned());
+        Some(Ok(config))
+    }
+
+    /// A builtin config which can be extended with [`PRESET_PREFIX`] followed by its name.
+    ///
+    /// `recommended` enables the builtin rules marked as recommended, which are almost always erroneous,
+    /// and `all` enables every builtin rule.
+    pub fn preset(name: &str) -> Option<Config> {
+        let rules = match name {
+            "recommended" => RulesConfig {
+                errors: CstRuleStore::new()
+                    .builtins()
+                    .rules
+                    .into_iter()
+                    .filter(|rule| rule.meta().recommended)
+                    .collect(),
+                ..RulesConfig::default()
+            },
+            "all" => RulesConfig {
+                groups: GROUP_NAMES.iter().map(|group| group.to_string()).collect(),
+                ..RulesConfig::default()
+            },
+            _ => return None,
+        };
+        Some(Config {
+            rules: Some(rules),
+            ..Config::default()
+        })
+    }
+
+    /// Parse a config file and merge it into the configs it extends.
+    /// `stack` holds the files which are currently being loaded to catch configs extending themselves.
+    fn load(path: &Path, stack: &mut Vec<PathBuf>) -> Option<Result<Self, ConfigError>> {
+        let source = read_to_string(path).ok()?;
+        Some(
+            Self::parse(path, &source)
+                .and_then(|(config, tree)| config.extend(path, &source, tree.as_ref(), stack)),
+        )
+    }
+
+    /// Parse a config file, and the spans of its keys and values if they can be parsed.
+    fn parse(path: &Path, source: &str) -> Result<(Self, Option<Node>), ConfigError> {
+        // spans can not be parsed for dates, in which case errors are pointed at their position only
+        let tree = from_str::<Node>(source).ok();
+        if let Some(problem) = tree.as_ref().and_then(validate) {
+            return Err(ConfigError::from_problem(path, source, problem));
+        }
+        match from_str::<Self>(source) {
+            Ok(config) => Ok((config, tree)),
+            Err(error) => Err(ConfigError::from_toml(path, source, tree.as_ref(), error)),
+        }
+    }
+
+    fn extend(
+        mut self,
+        path: &Path,
+        source: &str,
+        tree: Option<&Node>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Self, ConfigError> {
+        if self.extends.is_empty() {
+            return Ok(self);
+        }
+
+        // errors are pointed at the entry of `extends`
+        let error = |name: &str, message: String| {
+            let span = tree
+                .and_then(|tree| tree.get("extends"))
+                .and_then(|(_, extends)| {
+                    extends
+                        .get_ref()
+                        .elements()
+                        .find(|entry| *entry.get_ref() == Node::String(name.to_string()))
+                })
+                .map(|entry| entry.start()..entry.end());
+            ConfigError::new(path, source, message, span)
+        };
+        let dir = path.parent().unwrap_or(path);
+        stack.push(normalize(path));
+
+        let mut base = Config::default();
+        let mut overrides = vec![];
+        for name in std::mem::take(&mut self.extends) {
+            let mut config = if let Some(preset) = name.strip_prefix(PRESET_PREFIX) {
+                Self::preset(preset).ok_or_else(|| {
+                    error(
+                        &name,
+                        format!(
+                            "Unknown preset '{}', expected one of: {}",
+                            name,
+                            PRESET_NAMES
+                                .iter()
+                                .map(|preset| format!("{}{}", PRESET_PREFIX, preset))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                    )
+                })?
+            } else {
+                let extended = normalize(&dir.join(&name));
+                if stack.contains(&extended) {
+                    return Err(error(&name, format!("'{}' extends itself", name)));
+                }
+                Self::load(&extended, stack).ok_or_else(|| {
+                    error(
+                        &name,
+                        format!("Failed to read the extended config '{}'", name),
+                    )
+                })??
+            };
+            overrides.append(&mut config.overrides);
+            base.merge(config);
+        }
+
+        stack.pop();
+        overrides.append(&mut self.overrides);
+        base.merge(self);
+        base.overrides = overrides;
+        Ok(base)
+    }
+
+    /// The directory this config applies to, which is the directory of the config file.
+    pub fn dir(&self) -> Option<&Path> {
+        self.path.as_ref().and_then(|path| path.parent())
+    }
+
+    /// Merge a more specific config, such as the config of a subdirectory or a config extending
+    /// this config, into this config. Anything declared by `child` takes precedence over this config.
+    ///
+    /// The overrides of `child` are not merged, they are applied to each file by [`ConfigResolver`].
+    pub fn merge(&mut self, child: Config) {
+        self.root = child.root;
+        self.rules = match (self.rules.take(), child.rules) {
+            (Some(mut rules), Some(child_rules)) => {
+                rules.merge(child_rules);
+                Some(rules)
+            }
+            (rules, child_rules) => child_rules.or(rules),
+        };
+        self.env.extend(child.env);
+        self.globals.extend(child.globals);
+        self.ignore.extend(child.ignore);
+        self.gitignore = child.gitignore.or(self.gitignore);
+        self.extensions = child.extensions.or_else(|| self.extensions.take());
+        self.modules = child.modules.or(self.modules);
+        if child.path.is_some() {
+            self.path = child.path;
+        }
+    }
+
+    /// The level a rule of the [`store`](Config::store) of this config is run at.
+    pub fn rule_level(&self, rule_name: &str) -> RuleLevel {
+        self.rules.as_ref().map_or(RuleLevel::Error, |rules| {
+            rules.rule_level_by_name(rule_name)
+        })
+    }
+
+    /// Make a rule store with the rules and globals of this config.
+    /// All builtin rules are used if the config does not have a `rules` table.
+    pub fn store(&self) -> CstRuleStore {
+        let mut store = self
+            .rules
+            .as_ref()
+            .map_or_else(|| CstRuleStore::new().builtins(), RulesConfig::store);
+        store.globals = self.globals();
+        store
+    }
+
+    /// The globals declared by the `env` and `globals` sections, on top of the ES5 builtins.
+    ///
+    /// The default environments are enabled unless they are explicitly set to `false`.
+    pub fn globals(&self) -> Globals {
+        let mut globals = Globals::builtin();
+        let defaults = DEFAULT_ENVIRONMENTS
+            .iter()
+            .filter(|env| !self.env.contains_key(**env))
+            .copied();
+        let enabled = self
+            .env
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(env, _)| env.as_str());
+
+        for env in defaults.chain(enabled) {
+            if !globals.add_env(env) {
+                lint_warn!("Unknown environment '{}'", env);
+            }
+        }
+        for (name, kind) in &self.globals {
+            globals.insert(name.as_str(), *kind);
+        }
+        globals
+    }
+}
+
+/// The config of a rule as a table of its config fields, without the name of the rule type typetag wraps it in.
+///
+/// Fields which are not set are left out, so the config can also be written as TOML.
+pub fn rule_config(rule: &dyn CstRule) -> serde_json::Value {
+    let config = match serde_json::to_value(rule) {
+        Ok(serde_json::Value::Object(object)) => {
+            object.into_iter().next().map(|(_, config)| config)
+        }
+        _ => None,
+    };
+    let fields = match config {
+        Some(serde_json::Value::Object(fields)) => fields
+            .into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .collect(),
+        _ => Default::default(),
+    };
+    serde_json::Value::Object(fields)
+}
+
+/// Write a value on a single line, with inline tables instead of table headers.
+pub fn inline_toml(value: &toml::Value) -> String {
+    match value {
+        toml::Value::Table(table) if table.is_empty() => "{}".to_string(),
+        toml::Value::Table(table) => {
+            let entries = table
+                .iter()
+                .map(|(key, value)| format!("{} = {}", toml_key(key), inline_toml(value)))
+                .collect::<Vec<_>>();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        toml::Value::Array(array) => {
+            let items = array.iter().map(inline_toml).collect::<Vec<_>>();
+            format!("[{}]", items.join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+/// Write a key, quoting it if it is not a bare key.
+pub fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if bare {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
+
+impl Override {
+    /// Whether this override applies to a file.
+    pub fn matches(&self, path: impl AsRef<Path>) -> bool {
+        self.patterns
+            .as_ref()
+            .map_or(false, |patterns| patterns.is_match(path, false))
+    }
+
+    /// The config to merge into the config of files matching this override.
+    pub fn config(&self) -> Config {
+        Config {
+            rules: self.rules.clone(),
+            env: self.env.clone(),
+            globals: self.globals.clone(),
+            ..Config::default()
+        }
+    }
+}
+
+impl RulesConfig {
+    /// Merge the rules of a more specific config into these rules. Rules configured or allowed
+    /// by `child` replace the configuration and the level of the same rules in this config, and groups,
+    /// allowed rules, and levels are added to the ones of this config. Rules keep the order they were declared in,
+    /// with the rules of `child` last.
+    pub fn merge(&mut self, child: RulesConfig) {
+        let configured = child
+            .errors
+            .iter()
+            .chain(child.warnings.iter())
+            .map(|rule| rule.name())
+            .collect::<Vec<_>>();
+        let replaced = |name: &str| {
+            configured.contains(&name) || child.allowed.iter().any(|allowed| allowed == name)
+        };
+
+        self.errors.retain(|rule| !replaced(rule.name()));
+        self.warnings.retain(|rule| !replaced(rule.name()));
+        // giving an allowed rule a level runs it again
+        self.allowed.retain(|allowed| {
+            !configured.contains(&allowed.as_str())
+                && child.levels.get(allowed).copied().unwrap_or(RuleLevel::Off) == RuleLevel::Off
+        });
+        self.levels.retain(|name, _| !replaced(name));
+
+        for group in child.groups {
+            if !self.groups.contains(&group) {
+                self.groups.push(group);
+            }
+        }
+        for allowed in child.allowed {
+            if !self.allowed.contains(&allowed) {
+                self.allowed.push(allowed);
+            }
+        }
+        self.levels.extend(child.levels);
+        self.errors.extend(child.errors);
+        self.warnings.extend(child.warnings);
+    }
+
+    pub fn error_rule_names(&self) -> impl Iterator<Item = &str> {
+        // grouped rules are errors by default
+        self.errors
+            .iter()
+            .map(|rule| rule.name())
+            .chain(self.grouped_rules().map(|rule| rule.name()))
+    }
+
+    pub fn warning_rule_names(&self) -> impl Iterator<Item = &str> {
+        self.warnings.iter().map(|rule| rule.name())
+    }
+
+    /// The rules declared in the config using the `groups` field.
+    pub fn grouped_rules<'a>(&'a self) -> impl Iterator<Item = Box<dyn CstRule>> + 'a {
+        self.groups
+            .iter()
+            .filter_map(|group| get_group_rules_by_name(group))
+            .map(|rules| rules.into_iter())
+            .flatten()
+    }
+
+    /// The level of a rule, which is its level in `levels`, or the level of the table it is configured in.
+    pub fn rule_level_by_name(&self, rule_name: &str) -> RuleLevel {
+        if let Some(level) = self.levels.get(rule_name) {
+            *level
+        } else if self.warning_rule_names().any(|name| name == rule_name) {
+            RuleLevel::Warning
+        } else {
+            RuleLevel::Error
+        }
+    }
+
+    /// Remove any rules which are explicitly allowed by the `allowed` field
+    ///
+    /// if `issue_warnings` is true, linter warnings will be emitted stating a rule's configuration
+    /// was ignore because its explicitly allowed.
+    pub fn intersect_allowed<'a, T>(
+        &'a self,
+        rules: T,
+        issue_warnings: bool,
+    ) -> impl IntoIterator<Item = T::Item> + 'a
+    where
+        T: IntoIterator + 'a,
+        T::Item: Borrow<Box<dyn CstRule>>,
+    {
+        rules.into_iter().filter(move |rule| {
+            let res = self
+                .allowed
+                .iter()
+                .any(|allowed| allowed == rule.borrow().name());
+
+            if res && issue_warnings {
+                lint_warn!(
+                    "ignoring configuration for '{}' because it is explicitly allowed",
+                    rule.borrow().name()
+                );
+            }
+            !res
+        })
+    }
+
+    pub fn store(&self) -> CstRuleStore {
+        let mut store = CstRuleStore::new();
+        let mut rules: Vec<_> = self
+            .intersect_allowed(
+                Self::unique_rules(self.errors.clone(), self.warnings.clone()),
+                true,
+            )
+            .into_iter()
+            .collect();
+
+        for group in &self.groups {
+            if let Some(group_rules) = get_group_rules_by_name(&group) {
+                rules = Self::unique_rules(
+                    rules,
+                    self.intersect_allowed(group_rules.into_iter(), false)
+                        .into_iter()
+                        .collect(),
+                )
+                .collect();
+            } else {
+                lint_warn!("Unknown rule group '{}'", group);
+            }
+        }
+
+        // rules which are only given a level are run with their default config
+        for (name, level) in &self.levels {
+            if *level != RuleLevel::Off && !rules.iter().any(|rule| rule.name() == name) {
+                rules.extend(self.intersect_allowed(get_rule_by_name(name), false));
+            }
+        }
+
+        store.load_rules(rules);
+        for rule in store.rules.clone() {
+            store.set_level(rule.name(), self.rule_level_by_name(rule.name()));
+        }
+        store
+    }
+
+    #[allow(clippy::needless_collect)]
+    fn unique_rules(
+        first: Vec<Box<dyn CstRule>>,
+        second: Vec<Box<dyn CstRule>>,
+    ) -> impl Iterator<Item = Box<dyn CstRule>> {
+        // collecting is necessary because otherwise, filter's closure might outlive the current function
+        let filtered = second
+            .into_iter()
+            .filter(|rule| !first.iter().any(|prev| prev.name() == rule.name()))
+            .collect::<Vec<_>>();
+        first.into_iter().chain(filtered.into_iter())
+    }
+}
+
+/// Deserialize the levels of rules, with the names of the rules in kebab case.
+fn from_rule_levels<'de, D>(deserializer: D) -> Result<BTreeMap<String, RuleLevel>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let levels = BTreeMap::<String, RuleLevel>::deserialize(deserializer)?;
+    levels
+        .into_iter()
+        .map(|(name, level)| {
+            let rule = name.to_kebab_case();
+            if get_rule_by_name(&rule).is_some() {
+                Ok((rule, level))
+            } else if let Some(suggestion) = get_rule_suggestion(&rule) {
+                Err(D::Error::custom(format!(
+                    "Unknown rule '{}'. did you mean '{}'?",
+                    name, suggestion
+                )))
+            } else {
+                Err(D::Error::custom(format!("Unknown rule '{}'", name)))
+            }
+        })
+        .collect()
+}
+
+fn from_rule_objects<'de, D>(deserializer: D) -> Result<Vec<Box<dyn CstRule>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct TypetagObjects<T> {
+        _type: PhantomData<T>,
+    }
+
+    impl<'de> Visitor<'de> for TypetagObjects<Box<dyn CstRule>> {
+        type Value = Vec<Box<dyn CstRule>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("zero or more rule-to-config pairs")
+        }
+
+        fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+        where
+            M: MapAccess<'de>,
+        {
+            let mut vec = Vec::new();
+            while let Some(key) = map.next_key::<String>()? {
+                let de = MapAccessDeserializer::new(Entry {
+                    key: Some(key.to_camel_case().into_deserializer()),
+                    value: &mut map,
+                });
+                if get_rule_by_name(&key.to_kebab_case()).is_none() {
+                    if let Some(suggestion) = get_rule_suggestion(&key.to_kebab_case()) {
+                        return Err(M::Error::custom(format!(
+                            "Unknown rule '{}'. did you mean '{}'?",
+                            key, suggestion
+                        )));
+                    } else {
+                        return Err(M::Error::custom(format!("Unknown rule '{}'", key)));
+                    }
+                } else {
+                    vec.push(Box::<dyn CstRule>::deserialize(de)?);
+                }
+            }
+            Ok(vec)
+        }
+    }
+
+    struct Entry<K, V> {
+        key: Option<K>,
+        value: V,
+    }
+
+    impl<'de, K, V> MapAccess<'de> for Entry<K, V>
+    where
+        K: Deserializer<'de, Error = V::Error>,
+        V: MapAccess<'de>,
+    {
+        type Error = V::Error;
+
+        fn next_key_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+        where
+            S: DeserializeSeed<'de>,
+        {
+            self.key.take().map(|key| seed.deserialize(key)).transpose()
+        }
+
+        fn next_value_seed<S>(&mut self, seed: S) -> Result<S::Value, Self::Error>
+        where
+            S: DeserializeSeed<'de>,
+        {
+            self.value.next_value_seed(seed)
+        }
+    }
+
+    deserializer.deserialize_map(TypetagObjects { _type: PhantomData })
+}
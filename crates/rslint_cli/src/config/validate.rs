@@ -0,0 +1,439 @@
+//! Validation of config files with the spans of their keys and values,
+//! which points config errors at the offending part of the config.
+
+use crate::field_schema;
+use heck::{KebabCase, MixedCase};
+use rslint_core::{
+    get_rule_by_name, get_rule_suggestion, util::find_best_match_for_name, CstRule, RuleLevel,
+};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+use std::ops::Range;
+use toml::Spanned;
+
+/// A TOML value with the spans of its keys and values.
+///
+/// Tables declared with a header and tables in arrays of tables have an empty span,
+/// the span of their key should be used instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Table(Vec<(Spanned<String>, Spanned<Node>)>),
+    Array(Vec<Spanned<Node>>),
+    String(String),
+    Boolean,
+    Integer,
+    Float,
+}
+
+impl Node {
+    /// The key and value of an entry of a table.
+    pub fn get(&self, name: &str) -> Option<(&Spanned<String>, &Spanned<Node>)> {
+        match self {
+            Node::Table(entries) => entries
+                .iter()
+                .find(|(key, _)| key.get_ref() == name)
+                .map(|(key, value)| (key, value)),
+            _ => None,
+        }
+    }
+
+    /// The entries of a table, or nothing if this is not a table.
+    pub fn entries(&self) -> impl Iterator<Item = &(Spanned<String>, Spanned<Node>)> {
+        match self {
+            Node::Table(entries) => entries.as_slice(),
+            _ => &[],
+        }
+        .iter()
+    }
+
+    /// The elements of an array, or nothing if this is not an array.
+    pub fn elements(&self) -> impl Iterator<Item = &Spanned<Node>> {
+        match self {
+            Node::Array(elements) => elements.as_slice(),
+            _ => &[],
+        }
+        .iter()
+    }
+
+    /// The JSON Schema name of the type of this value.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Node::Table(_) => "object",
+            Node::Array(_) => "array",
+            Node::String(_) => "string",
+            Node::Boolean => "boolean",
+            Node::Integer => "integer",
+            Node::Float => "number",
+        }
+    }
+
+    /// The span of the smallest key or value which starts at an offset.
+    pub fn span_at(&self, offset: usize) -> Option<Range<usize>> {
+        let mut spans = vec![];
+        for (key, value) in self.entries() {
+            spans.push(key.start()..key.end());
+            spans.push(value.start()..value.end());
+            spans.extend(value.get_ref().span_at(offset));
+        }
+        for element in self.elements() {
+            spans.push(element.start()..element.end());
+            spans.extend(element.get_ref().span_at(offset));
+        }
+        spans
+            .into_iter()
+            .filter(|span| span.start == offset && span.end > offset)
+            .min_by_key(|span| span.len())
+    }
+}
+
+/// The span of a value, or of its key if the value was declared with a header.
+fn span(key: &Spanned<String>, value: &Spanned<Node>) -> Range<usize> {
+    if value.end() > value.start() {
+        value.start()..value.end()
+    } else {
+        key.start()..key.end()
+    }
+}
+
+fn describe(kind: &str) -> &'static str {
+    match kind {
+        "object" => "a table",
+        "array" => "an array",
+        "string" => "a string",
+        "boolean" => "a boolean",
+        "integer" => "an integer",
+        _ => "a number",
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NodeVisitor;
+
+        impl<'de> Visitor<'de> for NodeVisitor {
+            type Value = Node;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a TOML value")
+            }
+
+            fn visit_bool<E>(self, _: bool) -> Result<Node, E> {
+                Ok(Node::Boolean)
+            }
+
+            fn visit_i64<E>(self, _: i64) -> Result<Node, E> {
+                Ok(Node::Integer)
+            }
+
+            fn visit_u64<E>(self, _: u64) -> Result<Node, E> {
+                Ok(Node::Integer)
+            }
+
+            fn visit_f64<E>(self, _: f64) -> Result<Node, E> {
+                Ok(Node::Float)
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Node, E> {
+                Ok(Node::String(value.to_string()))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node, A::Error> {
+                let mut elements = vec![];
+                while let Some(element) = seq.next_element()? {
+                    elements.push(element);
+                }
+                Ok(Node::Array(elements))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node, A::Error> {
+                let mut entries = vec![];
+                while let Some(key) = map.next_key()? {
+                    entries.push((key, map.next_value()?));
+                }
+                Ok(Node::Table(entries))
+            }
+        }
+
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+/// A problem found in a config by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub message: String,
+    pub span: Range<usize>,
+    pub help: Option<String>,
+}
+
+/// Find the first unknown rule, unknown rule config field, rule config field with the wrong type,
+/// or invalid rule level of a config.
+pub fn validate(config: &Node) -> Option<Problem> {
+    let overrides = config
+        .get("overrides")
+        .into_iter()
+        .flat_map(|(_, overrides)| overrides.get_ref().elements())
+        .map(|override_| override_.get_ref());
+    std::iter::once(config)
+        .chain(overrides)
+        .filter_map(|config| config.get("rules"))
+        .find_map(|(_, rules)| {
+            let rules = rules.get_ref();
+            rules
+                .get("errors")
+                .into_iter()
+                .chain(rules.get("warnings"))
+                .flat_map(|(_, rules)| rules.get_ref().entries())
+                .find_map(|(name, config)| validate_rule(name, config))
+                .or_else(|| {
+                    rules
+                        .get("levels")
+                        .into_iter()
+                        .flat_map(|(_, levels)| levels.get_ref().entries())
+                        .find_map(|(name, level)| validate_level(name, level))
+                })
+        })
+}
+
+fn find_rule(name: &Spanned<String>) -> Result<Box<dyn CstRule>, Problem> {
+    get_rule_by_name(&name.get_ref().to_kebab_case()).ok_or_else(|| Problem {
+        message: format!("Unknown rule '{}'", name.get_ref()),
+        span: name.start()..name.end(),
+        help: get_rule_suggestion(&name.get_ref().to_kebab_case())
+            .map(|suggestion| format!("did you mean '{}'?", suggestion)),
+    })
+}
+
+fn validate_level(name: &Spanned<String>, level: &Spanned<Node>) -> Option<Problem> {
+    let rule = match find_rule(name) {
+        Ok(rule) => rule,
+        Err(problem) => return Some(problem),
+    };
+    match level.get_ref() {
+        Node::String(level) if level.parse::<RuleLevel>().is_ok() => None,
+        node => Some(Problem {
+            message: match node {
+                Node::String(value) => {
+                    format!("Invalid level '{}' of rule '{}'", value, rule.name())
+                }
+                node => format!(
+                    "Expected the level of '{}', found {}",
+                    rule.name(),
+                    describe(node.kind())
+                ),
+            },
+            span: span(name, level),
+            help: Some("the level of a rule must be 'off', 'info', 'warn', or 'error'".to_string()),
+        }),
+    }
+}
+
+fn validate_rule(name: &Spanned<String>, config: &Spanned<Node>) -> Option<Problem> {
+    let rule = match find_rule(name) {
+        Ok(rule) => rule,
+        Err(problem) => return Some(problem),
+    };
+
+    if !matches!(config.get_ref(), Node::Table(_)) {
+        return Some(Problem {
+            message: format!(
+                "Expected a table with the config of '{}', found {}",
+                rule.name(),
+                describe(config.get_ref().kind())
+            ),
+            span: span(name, config),
+            help: Some(format!(
+                "use `{} = {{}}` to use the default config",
+                name.get_ref()
+            )),
+        });
+    }
+
+    let fields = rule.meta().config;
+    for (key, value) in config.get_ref().entries() {
+        let field = match fields.iter().find(|field| &field.name == key.get_ref()) {
+            Some(field) => field,
+            None => {
+                let camel = key.get_ref().to_mixed_case();
+                let help = if fields.is_empty() {
+                    Some(format!("'{}' has no config", rule.name()))
+                } else {
+                    fields
+                        .iter()
+                        .find(|field| field.name == camel)
+                        .map(|field| field.name.as_str())
+                        .or_else(|| {
+                            find_best_match_for_name(
+                                fields.iter().map(|field| field.name.as_str()),
+                                key.get_ref(),
+                                None,
+                            )
+                        })
+                        .map(|suggestion| format!("did you mean '{}'?", suggestion))
+                };
+                return Some(Problem {
+                    message: format!(
+                        "Unknown config field '{}' of rule '{}'",
+                        key.get_ref(),
+                        rule.name()
+                    ),
+                    span: key.start()..key.end(),
+                    help,
+                });
+            }
+        };
+
+        let schema = field_schema(field);
+        if let Some(problem) = check_type(&schema, key, value) {
+            return Some(Problem {
+                message: format!(
+                    "Invalid config field '{}' of rule '{}': {}",
+                    key.get_ref(),
+                    rule.name(),
+                    problem.message
+                ),
+                ..problem
+            });
+        }
+    }
+    None
+}
+
+/// Check a value against the type, the names, and the format of a schema made by [`field_schema`].
+fn check_type(
+    schema: &serde_json::Value,
+    key: &Spanned<String>,
+    value: &Spanned<Node>,
+) -> Option<Problem> {
+    let expected = schema["type"].as_str()?;
+    let found = value.get_ref().kind();
+    if expected != found && !(expected == "number" && found == "integer") {
+        return Some(Problem {
+            message: format!("expected {}, found {}", describe(expected), describe(found)),
+            span: span(key, value),
+            help: None,
+        });
+    }
+    if let Node::String(string) = value.get_ref() {
+        let names = schema["enum"].as_array().into_iter().flatten();
+        let names = names.filter_map(|name| name.as_str()).collect::<Vec<_>>();
+        if !names.is_empty() && !names.contains(&string.as_str()) {
+            return Some(Problem {
+                message: format!(
+                    "expected one of {}, found '{}'",
+                    names
+                        .iter()
+                        .map(|name| format!("'{}'", name))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    string
+                ),
+                span: span(key, value),
+                help: find_best_match_for_name(names.iter().copied(), string, None)
+                    .map(|suggestion| format!("did you mean '{}'?", suggestion)),
+            });
+        }
+        if schema["format"] == "regex" {
+            if let Err(error) = rslint_core::util::Pattern::new(string) {
+                return Some(Problem {
+                    message: format!("invalid regular expression: {}", error),
+                    span: span(key, value),
+                    help: None,
+                });
+            }
+        }
+    }
+    value
+        .get_ref()
+        .elements()
+        .find_map(|element| check_type(&schema["items"], key, element))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(source: &str) -> Option<(String, &str, Option<String>)> {
+        let problem = validate(&toml::from_str(source).unwrap())?;
+        Some((problem.message, &source[problem.span], problem.help))
+    }
+
+    #[test]
+    fn reports_problems_of_rule_configs() {
+        assert_eq!(
+            problem("[rules.errors]\nno-emtpy = {}"),
+            Some((
+                "Unknown rule 'no-emtpy'".to_string(),
+                "no-emtpy",
+                Some("did you mean 'no-empty'?".to_string())
+            ))
+        );
+        assert_eq!(
+            problem("[[overrides]]\nfiles = []\n[overrides.rules.warnings.no-empty]\nallow_empty_catch = true"),
+            Some((
+                "Unknown config field 'allow_empty_catch' of rule 'no-empty'".to_string(),
+                "allow_empty_catch",
+                Some("did you mean 'allowEmptyCatch'?".to_string())
+            ))
+        );
+        assert_eq!(
+            problem("[rules.errors]\nno-inner-declarations = { disallowed = [\"functions\", 5] }"),
+            Some((
+                "Invalid config field 'disallowed' of rule 'no-inner-declarations': expected a string, found an integer".to_string(),
+                "5",
+                None
+            ))
+        );
+        assert_eq!(
+            problem("[rules.errors]\nno-empty = true").map(|problem| problem.1),
+            Some("true")
+        );
+        assert_eq!(
+            problem("[rules.errors]\nno-unused-vars = { args = \"after_used\" }"),
+            Some((
+                "Invalid config field 'args' of rule 'no-unused-vars': expected one of 'after-used', 'all', 'none', found 'after_used'".to_string(),
+                "\"after_used\"",
+                Some("did you mean 'after-used'?".to_string())
+            ))
+        );
+        assert_eq!(
+            problem("[rules.errors]\nno-unused-vars = { ignorePattern = \"^(_\" }")
+                .map(|problem| problem.1),
+            Some("\"^(_\"")
+        );
+        assert_eq!(
+            problem("[rules.errors]\nno-empty = { allowEmptyCatch = true }\nno-debugger = {}"),
+            None
+        );
+        assert_eq!(
+            problem(
+                "[rules.errors]\nno-unused-vars = { vars = \"local\", ignorePattern = \"^_\" }"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn reports_problems_of_rule_levels() {
+        assert_eq!(
+            problem("[rules.levels]\nno-empty = \"warning\""),
+            Some((
+                "Invalid level 'warning' of rule 'no-empty'".to_string(),
+                "\"warning\"",
+                Some("the level of a rule must be 'off', 'info', 'warn', or 'error'".to_string())
+            ))
+        );
+        assert_eq!(
+            problem("[rules]\nlevels = { no-debuger = \"off\" }").map(|problem| problem.1),
+            Some("no-debuger")
+        );
+        assert_eq!(
+            problem("[rules.levels]\nno-empty = 1").map(|problem| problem.0),
+            Some("Expected the level of 'no-empty', found an integer".to_string())
+        );
+        assert_eq!(
+            problem("[rules.levels]\nno-empty = \"info\"\nnoDebugger = \"off\""),
+            None
+        );
+    }
+}
@@ -1,12 +1,13 @@
 //! The structure responsible for managing IO and the files implementation for codespan.
 
+use crate::{ChangedFiles, DirFilters, ModuleSystem};
 use codespan_reporting::files::Files;
 use glob::Paths;
 use hashbrown::HashMap;
 use std::borrow::Cow;
 use std::fs::read_to_string;
 use std::ops::Range;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread::Builder;
 use walkdir::WalkDir;
@@ -14,11 +15,6 @@ use walkdir::WalkDir;
 // 0 is reserved for "no file id" (virtual files)
 static FILE_ID_COUNTER: AtomicUsize = AtomicUsize::new(1);
 
-/// A list of ignored-by-default directory/file names
-const IGNORED: [&str; 1] = ["node_modules"];
-/// A list of the extension of files linted
-const LINTED_FILES: [&str; 2] = ["js", "mjs"];
-
 /// The structure for managing IO to and from the core runner.
 /// The walker uses multithreaded IO, spawning a thread for every file being loaded.
 // TODO: use IO_Uring for linux
@@ -67,34 +63,32 @@ impl FileWalker {
         }
     }
 
-    /// Make a new file walker from a compiled glob pattern. This also
-    /// skips any unreadable files/dirs
-    pub fn from_glob(paths: Paths) -> Self {
+    /// Make a new file walker from a compiled glob pattern, only loading the files accepted by the filter
+    /// of their directory, and only the files which changed if `changed` is given.
+    /// This also skips any unreadable files/dirs
+    pub fn from_glob(
+        paths: Paths,
+        filters: &mut DirFilters,
+        changed: Option<&ChangedFiles>,
+    ) -> Self {
         let mut threads = Vec::new();
         for entry in paths.filter_map(Result::ok) {
-            if IGNORED.contains(
-                &entry
-                    .file_name()
-                    .map(|x| x.to_string_lossy().to_string())
-                    .unwrap_or_default()
-                    .as_str(),
-            ) {
+            if filters.is_ignored(&entry, entry.is_dir()) {
                 continue;
             }
 
-            let walkdir = WalkDir::new(entry)
-                .into_iter()
-                .filter_entry(|entry| !IGNORED.contains(&&*entry.file_name().to_string_lossy()));
+            let walkdir = WalkDir::new(entry).into_iter().filter_entry(|entry| {
+                let path = entry.path();
+                let is_dir = entry.file_type().is_dir();
+                let filter = filters.get(path.parent().unwrap_or(path));
+                !filter.is_ignored_entry(path, is_dir)
+                    && (is_dir || filter.has_linted_extension(path))
+            });
 
             for file in walkdir.filter_map(Result::ok) {
-                if !LINTED_FILES.contains(
-                    &file
-                        .path()
-                        .extension()
-                        .map(|osstr| osstr.to_string_lossy().to_string())
-                        .unwrap_or_default()
-                        .as_str(),
-                ) {
+                if !file.file_type().is_file()
+                    || matches!(changed, Some(changed) if !changed.contains(file.path()))
+                {
                     continue;
                 }
                 // Give each io thread a name so we can potentially debug any io failures easily
@@ -149,10 +143,10 @@ pub enum JsFileKind {
     Module,
 }
 
-impl JsFile {
-    pub fn new_concrete(source: String, path: PathBuf) -> Self {
-        let id = FILE_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
-        let kind = if path
+impl JsFileKind {
+    /// The kind of a file based on its extension, `.mjs` files are modules and anything else is a script.
+    pub fn from_path(path: &Path) -> Self {
+        if path
             .extension()
             .map_or("".into(), |ext| ext.to_string_lossy())
             == "mjs"
@@ -160,7 +154,24 @@ impl JsFile {
             JsFileKind::Module
         } else {
             JsFileKind::Script
-        };
+        }
+    }
+
+    /// The kind of a file based on its extension and the module system of its config,
+    /// with `esm` anything but `.cjs` files is a module.
+    pub fn from_config(path: &Path, modules: Option<ModuleSystem>) -> Self {
+        if modules == Some(ModuleSystem::Esm) && path.extension() != Some("cjs".as_ref()) {
+            JsFileKind::Module
+        } else {
+            Self::from_path(path)
+        }
+    }
+}
+
+impl JsFile {
+    pub fn new_concrete(source: String, path: PathBuf) -> Self {
+        let id = FILE_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let kind = JsFileKind::from_path(&path);
         let line_starts = Self::line_starts(&source).collect();
 
         Self {
@@ -175,6 +186,29 @@ impl JsFile {
         }
     }
 
+    /// Make a file which is not on disk, such as source read from stdin.
+    /// Whether the file is a script or a module is decided by the extension of its name.
+    pub fn new_virtual(source: String, name: String) -> Self {
+        let id = FILE_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let kind = JsFileKind::from_path(Path::new(&name));
+        let line_starts = Self::line_starts(&source).collect();
+
+        Self {
+            source,
+            name,
+            path: None,
+            id,
+            kind,
+            line_starts,
+        }
+    }
+
+    /// Replace the source of this file, recomputing its line starts.
+    pub fn update_src(&mut self, new: String) {
+        self.line_starts = Self::line_starts(&new).collect();
+        self.source = new;
+    }
+
     fn line_starts<'a>(source: &'a str) -> impl Iterator<Item = usize> + 'a {
         std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1))
     }
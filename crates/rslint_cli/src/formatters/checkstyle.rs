@@ -0,0 +1,54 @@
+use super::{file_name, location, primary_label, sorted_diagnostics, xml_escape, Formatter};
+use crate::FileWalker;
+use codespan_reporting::diagnostic::Severity;
+use codespan_reporting::term::termcolor::WriteColor;
+use rslint_core::LintResult;
+use std::io;
+
+/// A formatter which outputs checkstyle XML, with a `file` element for every linted file.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CheckstyleFormatter;
+
+impl Formatter for CheckstyleFormatter {
+    fn format(
+        &mut self,
+        results: &[LintResult],
+        files: &FileWalker,
+        writer: &mut dyn WriteColor,
+    ) -> io::Result<()> {
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+        writeln!(writer, "<checkstyle version=\"4.3\">")?;
+
+        for result in results {
+            let name = file_name(files, result.file_id);
+            writeln!(writer, "  <file name=\"{}\">", xml_escape(&name))?;
+
+            for diagnostic in sorted_diagnostics(result) {
+                let loc = location(
+                    files,
+                    result.file_id,
+                    primary_label(diagnostic).map_or(0, |label| label.range.start),
+                );
+                let severity = match diagnostic.severity {
+                    Severity::Bug | Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Note | Severity::Help => "info",
+                };
+                let source = diagnostic.code.as_deref().unwrap_or("linter");
+
+                writeln!(
+                    writer,
+                    "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"rslint.{}\" />",
+                    loc.line_number,
+                    loc.column_number,
+                    severity,
+                    xml_escape(&diagnostic.message),
+                    xml_escape(source)
+                )?;
+            }
+            writeln!(writer, "  </file>")?;
+        }
+
+        writeln!(writer, "</checkstyle>")
+    }
+}
@@ -0,0 +1,43 @@
+use super::{file_name, location, primary_label, severity_name, sorted_diagnostics, Formatter};
+use crate::FileWalker;
+use codespan_reporting::term::termcolor::WriteColor;
+use rslint_core::LintResult;
+use std::io;
+
+/// A formatter which outputs a single `path:line:col: severity [rule] message` line for every diagnostic,
+/// this is the format used by tools such as grep, and it can be read by vim's quickfix list.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CompactFormatter;
+
+impl Formatter for CompactFormatter {
+    fn format(
+        &mut self,
+        results: &[LintResult],
+        files: &FileWalker,
+        writer: &mut dyn WriteColor,
+    ) -> io::Result<()> {
+        for result in results {
+            let name = file_name(files, result.file_id);
+            for diagnostic in sorted_diagnostics(result) {
+                let loc = location(
+                    files,
+                    result.file_id,
+                    primary_label(diagnostic).map_or(0, |label| label.range.start),
+                );
+                let code = diagnostic.code.as_deref().unwrap_or("linter");
+
+                writeln!(
+                    writer,
+                    "{}:{}:{}: {} [{}] {}",
+                    name,
+                    loc.line_number,
+                    loc.column_number,
+                    severity_name(diagnostic.severity),
+                    code,
+                    diagnostic.message.replace('\n', " ")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use super::{file_name, location, primary_label, sorted_diagnostics, Formatter};
+use crate::FileWalker;
+use codespan_reporting::diagnostic::Severity;
+use codespan_reporting::term::termcolor::WriteColor;
+use rslint_core::LintResult;
+use std::io;
+
+/// A formatter which outputs GitHub Actions workflow commands, such as `::error file=…,line=…,col=…::message`,
+/// which show up as annotations on pull requests.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct GithubFormatter;
+
+impl Formatter for GithubFormatter {
+    fn format(
+        &mut self,
+        results: &[LintResult],
+        files: &FileWalker,
+        writer: &mut dyn WriteColor,
+    ) -> io::Result<()> {
+        for result in results {
+            let name = file_name(files, result.file_id);
+            let name = name.trim_start_matches("./");
+
+            for diagnostic in sorted_diagnostics(result) {
+                let command = match diagnostic.severity {
+                    Severity::Bug | Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Note | Severity::Help => "notice",
+                };
+                let range = primary_label(diagnostic).map_or(0..0, |label| label.range.clone());
+                let start = location(files, result.file_id, range.start);
+                let end = location(files, result.file_id, range.end);
+                let code = diagnostic.code.as_deref().unwrap_or("linter");
+
+                let mut message = diagnostic.message.clone();
+                for note in &diagnostic.notes {
+                    message.push('\n');
+                    message.push_str(note);
+                }
+
+                writeln!(
+                    writer,
+                    "::{} file={},line={},col={},endLine={},endColumn={},title={}::{}",
+                    command,
+                    escape_property(name),
+                    start.line_number,
+                    start.column_number,
+                    end.line_number,
+                    end.column_number,
+                    escape_property(&format!("rslint({})", code)),
+                    escape_data(&message)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escape the message of a workflow command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape the value of a workflow command property, which additionally can't contain `:` or `,`.
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
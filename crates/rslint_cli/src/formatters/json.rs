@@ -0,0 +1,105 @@
+use super::{file_name, location, primary_label, severity_name, sorted_diagnostics, Formatter};
+use crate::FileWalker;
+use codespan_reporting::diagnostic::LabelStyle;
+use codespan_reporting::term::termcolor::WriteColor;
+use rslint_core::LintResult;
+use serde::Serialize;
+use std::io;
+use std::ops::Range;
+
+/// A formatter which outputs a JSON array with an object for every diagnostic.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct JsonFormatter;
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    file: String,
+    code: Option<&'a str>,
+    severity: &'static str,
+    message: &'a str,
+    range: Option<JsonRange>,
+    labels: Vec<JsonLabel<'a>>,
+    notes: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonLabel<'a> {
+    style: &'static str,
+    message: &'a str,
+    range: JsonRange,
+}
+
+#[derive(Serialize)]
+struct JsonRange {
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+/// A position in a file, `line` and `column` are one based and `column` counts characters.
+#[derive(Serialize)]
+struct JsonPosition {
+    byte: usize,
+    line: usize,
+    column: usize,
+}
+
+impl JsonRange {
+    fn new(files: &FileWalker, file_id: usize, range: Range<usize>) -> Self {
+        let position = |byte| {
+            let loc = location(files, file_id, byte);
+            JsonPosition {
+                byte,
+                line: loc.line_number,
+                column: loc.column_number,
+            }
+        };
+
+        Self {
+            start: position(range.start),
+            end: position(range.end),
+        }
+    }
+}
+
+impl Formatter for JsonFormatter {
+    fn format(
+        &mut self,
+        results: &[LintResult],
+        files: &FileWalker,
+        writer: &mut dyn WriteColor,
+    ) -> io::Result<()> {
+        let mut diagnostics = vec![];
+
+        for result in results {
+            let file = file_name(files, result.file_id);
+            for diagnostic in sorted_diagnostics(result) {
+                let labels = diagnostic
+                    .labels
+                    .iter()
+                    .map(|label| JsonLabel {
+                        style: match label.style {
+                            LabelStyle::Primary => "primary",
+                            LabelStyle::Secondary => "secondary",
+                        },
+                        message: &label.message,
+                        range: JsonRange::new(files, label.file_id, label.range.clone()),
+                    })
+                    .collect();
+
+                diagnostics.push(JsonDiagnostic {
+                    file: file.clone(),
+                    code: diagnostic.code.as_deref(),
+                    severity: severity_name(diagnostic.severity),
+                    message: &diagnostic.message,
+                    range: primary_label(diagnostic)
+                        .map(|label| JsonRange::new(files, label.file_id, label.range.clone())),
+                    labels,
+                    notes: &diagnostic.notes,
+                });
+            }
+        }
+
+        serde_json::to_writer_pretty(&mut *writer, &diagnostics)?;
+        writeln!(writer)
+    }
+}
@@ -0,0 +1,82 @@
+use super::{
+    file_name, location, primary_label, severity_name, sorted_diagnostics, xml_escape, Formatter,
+};
+use crate::FileWalker;
+use codespan_reporting::diagnostic::Severity;
+use codespan_reporting::term::termcolor::WriteColor;
+use rslint_core::LintResult;
+use std::io;
+
+/// A formatter which outputs JUnit XML, with a test suite for every linted file and a failing
+/// test case for every error or warning. Files without problems get a single passing test case.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct JunitFormatter;
+
+impl Formatter for JunitFormatter {
+    fn format(
+        &mut self,
+        results: &[LintResult],
+        files: &FileWalker,
+        writer: &mut dyn WriteColor,
+    ) -> io::Result<()> {
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+        writeln!(writer, "<testsuites>")?;
+
+        for result in results {
+            let name = file_name(files, result.file_id);
+            let name = xml_escape(&name);
+            let failures = sorted_diagnostics(result)
+                .into_iter()
+                .filter(|diagnostic| diagnostic.severity >= Severity::Warning)
+                .collect::<Vec<_>>();
+
+            writeln!(
+                writer,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"0\">",
+                name,
+                failures.len().max(1),
+                failures.len()
+            )?;
+
+            if failures.is_empty() {
+                writeln!(
+                    writer,
+                    "    <testcase name=\"{}\" classname=\"{}\" time=\"0\" />",
+                    name, name
+                )?;
+            }
+
+            for diagnostic in failures {
+                let loc = location(
+                    files,
+                    result.file_id,
+                    primary_label(diagnostic).map_or(0, |label| label.range.start),
+                );
+                let code = diagnostic.code.as_deref().unwrap_or("linter");
+                let severity = severity_name(diagnostic.severity);
+
+                writeln!(
+                    writer,
+                    "    <testcase name=\"rslint.{}\" classname=\"{}\" time=\"0\">",
+                    xml_escape(code),
+                    name
+                )?;
+                writeln!(
+                    writer,
+                    "      <failure message=\"{}\" type=\"{}\">line {}, col {}, {} - {} ({})</failure>",
+                    xml_escape(&diagnostic.message),
+                    severity,
+                    loc.line_number,
+                    loc.column_number,
+                    severity,
+                    xml_escape(&diagnostic.message),
+                    xml_escape(code)
+                )?;
+                writeln!(writer, "    </testcase>")?;
+            }
+            writeln!(writer, "  </testsuite>")?;
+        }
+
+        writeln!(writer, "</testsuites>")
+    }
+}
@@ -0,0 +1,26 @@
+use super::Formatter;
+use crate::{codespan_config, FileWalker};
+use codespan_reporting::term::{emit, termcolor::WriteColor};
+use rslint_core::LintResult;
+use std::io;
+
+/// The default formatter, which renders rich diagnostics with codespan.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LongFormatter;
+
+impl Formatter for LongFormatter {
+    fn format(
+        &mut self,
+        results: &[LintResult],
+        files: &FileWalker,
+        writer: &mut dyn WriteColor,
+    ) -> io::Result<()> {
+        for result in results {
+            for diagnostic in result.diagnostics() {
+                emit(writer, &codespan_config(), files, diagnostic)
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+//! Reporters which output the results of a lint run in different formats.
+//!
+//! Each format is implemented as a [`Formatter`] over the results of the entire run,
+//! new formats can be added by implementing the trait and adding a variant to [`OutputFormat`].
+
+mod checkstyle;
+mod compact;
+mod github;
+mod json;
+mod junit;
+mod long;
+mod sarif;
+
+pub use self::{
+    checkstyle::CheckstyleFormatter, compact::CompactFormatter, github::GithubFormatter,
+    json::JsonFormatter, junit::JunitFormatter, long::LongFormatter, sarif::SarifFormatter,
+};
+
+use crate::FileWalker;
+use codespan_reporting::diagnostic::{Label, LabelStyle, Severity};
+use codespan_reporting::files::{Files, Location};
+use codespan_reporting::term::termcolor::WriteColor;
+use rslint_core::{Diagnostic, LintResult};
+use std::borrow::Cow;
+use std::io;
+use std::str::FromStr;
+
+/// A reporter which writes the results of a lint run.
+pub trait Formatter {
+    /// Write the results of every linted file to `writer`, `files` is used to resolve
+    /// the names of files, and the lines and columns of diagnostics.
+    fn format(
+        &mut self,
+        results: &[LintResult],
+        files: &FileWalker,
+        writer: &mut dyn WriteColor,
+    ) -> io::Result<()>;
+}
+
+/// The output formats which can be selected with `--format`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OutputFormat {
+    /// Rich diagnostics rendered with codespan, this is the default.
+    Long,
+    /// One line per diagnostic, `unix` is an alias of this format.
+    Compact,
+    /// GitHub Actions workflow commands.
+    Github,
+    Json,
+    Sarif,
+    Checkstyle,
+    Junit,
+}
+
+impl OutputFormat {
+    /// The names of every format as accepted by `--format`.
+    pub const NAMES: [&'static str; 8] = [
+        "long",
+        "compact",
+        "unix",
+        "github",
+        "json",
+        "sarif",
+        "checkstyle",
+        "junit",
+    ];
+
+    /// Make a new formatter for this format.
+    pub fn formatter(self) -> Box<dyn Formatter> {
+        match self {
+            OutputFormat::Long => Box::new(LongFormatter),
+            OutputFormat::Compact => Box::new(CompactFormatter),
+            OutputFormat::Github => Box::new(GithubFormatter),
+            OutputFormat::Json => Box::new(JsonFormatter),
+            OutputFormat::Sarif => Box::new(SarifFormatter),
+            OutputFormat::Checkstyle => Box::new(CheckstyleFormatter),
+            OutputFormat::Junit => Box::new(JunitFormatter),
+        }
+    }
+
+    /// Whether this format is meant to be read by other tools, machine readable formats
+    /// are written to stdout without colors or a summary of the run.
+    pub fn is_machine_readable(self) -> bool {
+        self != OutputFormat::Long
+    }
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Long
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "long" => OutputFormat::Long,
+            "compact" | "unix" => OutputFormat::Compact,
+            "github" => OutputFormat::Github,
+            "json" => OutputFormat::Json,
+            "sarif" => OutputFormat::Sarif,
+            "checkstyle" => OutputFormat::Checkstyle,
+            "junit" => OutputFormat::Junit,
+            _ => {
+                return Err(format!(
+                    "unknown format '{}', expected one of: {}",
+                    s,
+                    Self::NAMES.join(", ")
+                ))
+            }
+        })
+    }
+}
+
+/// The diagnostics of a result sorted by the position of their primary label, rules run in parallel
+/// so the diagnostics of a result are otherwise not in a stable order.
+pub(crate) fn sorted_diagnostics<'a>(result: &'a LintResult) -> Vec<&'a Diagnostic> {
+    let mut diagnostics = result.diagnostics().collect::<Vec<_>>();
+    diagnostics.sort_by_key(|diagnostic| {
+        primary_label(diagnostic).map(|label| (label.range.start, label.range.end))
+    });
+    diagnostics
+}
+
+pub(crate) fn primary_label(diagnostic: &Diagnostic) -> Option<&Label<usize>> {
+    diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .or_else(|| diagnostic.labels.first())
+}
+
+/// The name of a file as shown to the user, which is its path for files on disk.
+pub(crate) fn file_name(files: &FileWalker, file_id: usize) -> String {
+    files
+        .name(file_id)
+        .map(|name| name.into_owned())
+        .unwrap_or_default()
+}
+
+/// The one based line and column of a byte index in a file.
+pub(crate) fn location(files: &FileWalker, file_id: usize, byte_index: usize) -> Location {
+    files.location(file_id, byte_index).unwrap_or(Location {
+        line_number: 1,
+        column_number: 1,
+    })
+}
+
+pub(crate) fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    }
+}
+
+/// Escape text for use in XML attributes and elements.
+pub(crate) fn xml_escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(|c| matches!(c, '<' | '>' | '&' | '"' | '\'')) {
+        return text.into();
+    }
+
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{JsFile, JsFileKind};
+    use codespan_reporting::term::termcolor::NoColor;
+    use rslint_core::{groups::errors::NoEmpty, lint_file, CstRuleStore, RuleLevel};
+    use std::path::PathBuf;
+
+    const SOURCE: &str = "let a = 5;\nif (a) {}\n";
+
+    fn format(format: OutputFormat) -> String {
+        format_with_level(format, RuleLevel::Error)
+    }
+
+    fn format_with_level(format: OutputFormat, level: RuleLevel) -> String {
+        let file = JsFile {
+            source: SOURCE.to_string(),
+            name: "foo.js".to_string(),
+            path: Some(PathBuf::from("src/foo.js")),
+            id: 1,
+            kind: JsFileKind::Script,
+            line_starts: vec![0, 11, 21],
+        };
+        let mut walker = FileWalker::empty();
+        walker.files.insert(1, file);
+
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![Box::new(NoEmpty::default()) as Box<_>]);
+        store.set_level("no-empty", level);
+        let result = lint_file(1, SOURCE, false, &store, false).unwrap();
+
+        let mut out = NoColor::new(vec![]);
+        format
+            .formatter()
+            .format(&[result], &walker, &mut out)
+            .unwrap();
+        String::from_utf8(out.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn parse_formats() {
+        for name in OutputFormat::NAMES.iter() {
+            assert!(name.parse::<OutputFormat>().is_ok());
+        }
+        assert_eq!("JSON".parse(), Ok(OutputFormat::Json));
+        assert_eq!("unix".parse(), Ok(OutputFormat::Compact));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(
+            format(OutputFormat::Compact),
+            "src/foo.js:2:8: error [no-empty] empty block statements are not allowed\n"
+        );
+        assert_eq!(
+            format_with_level(OutputFormat::Compact, RuleLevel::Warning),
+            "src/foo.js:2:8: warning [no-empty] empty block statements are not allowed\n"
+        );
+        assert_eq!(
+            format_with_level(OutputFormat::Compact, RuleLevel::Info),
+            "src/foo.js:2:8: note [no-empty] empty block statements are not allowed\n"
+        );
+        assert_eq!(format_with_level(OutputFormat::Compact, RuleLevel::Off), "");
+    }
+
+    #[test]
+    fn github() {
+        assert_eq!(
+            format(OutputFormat::Github),
+            "::error file=src/foo.js,line=2,col=8,endLine=2,endColumn=10,title=rslint(no-empty)::empty block statements are not allowed\n"
+        );
+        assert!(
+            format_with_level(OutputFormat::Github, RuleLevel::Warning).starts_with("::warning ")
+        );
+        assert!(format_with_level(OutputFormat::Github, RuleLevel::Info).starts_with("::notice "));
+    }
+
+    #[test]
+    fn json() {
+        let out = format(OutputFormat::Json);
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        let diagnostic = &value[0];
+
+        assert_eq!(diagnostic["file"], "src/foo.js");
+        assert_eq!(diagnostic["code"], "no-empty");
+        assert_eq!(diagnostic["severity"], "error");
+        assert_eq!(diagnostic["range"]["start"]["byte"], 18);
+        assert_eq!(diagnostic["range"]["start"]["line"], 2);
+        assert_eq!(diagnostic["range"]["start"]["column"], 8);
+        assert_eq!(diagnostic["range"]["end"]["column"], 10);
+        assert_eq!(diagnostic["labels"][0]["style"], "primary");
+    }
+
+    #[test]
+    fn sarif() {
+        let out = format(OutputFormat::Sarif);
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        let run = &value["runs"][0];
+
+        assert_eq!(value["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "no-empty");
+        assert_eq!(run["results"][0]["ruleId"], "no-empty");
+        assert_eq!(run["results"][0]["ruleIndex"], 0);
+        assert_eq!(run["results"][0]["level"], "error");
+
+        let location = &run["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/foo.js");
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["startColumn"], 8);
+    }
+
+    #[test]
+    fn checkstyle() {
+        let out = format(OutputFormat::Checkstyle);
+        assert!(out.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>"));
+        assert!(out.contains("<file name=\"src/foo.js\">"));
+        assert!(out.contains("line=\"2\" column=\"8\" severity=\"error\""));
+        assert!(out.contains("source=\"rslint.no-empty\""));
+    }
+
+    #[test]
+    fn junit() {
+        let out = format(OutputFormat::Junit);
+        assert!(out.contains("<testsuite name=\"src/foo.js\" tests=\"1\" failures=\"1\""));
+        assert!(out.contains("<testcase name=\"rslint.no-empty\" classname=\"src/foo.js\""));
+        assert!(out.contains("<failure message=\"empty block statements are not allowed\""));
+    }
+
+    #[test]
+    fn xml_escapes() {
+        assert_eq!(
+            xml_escape("a < b && \"c\""),
+            "a &lt; b &amp;&amp; &quot;c&quot;"
+        );
+        assert!(matches!(xml_escape("abc"), Cow::Borrowed(_)));
+    }
+}
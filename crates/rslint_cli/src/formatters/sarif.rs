@@ -0,0 +1,134 @@
+use super::{file_name, location, primary_label, sorted_diagnostics, Formatter};
+use crate::{FileWalker, REPO_LINK};
+use codespan_reporting::diagnostic::{LabelStyle, Severity};
+use codespan_reporting::term::termcolor::WriteColor;
+use rslint_core::{get_rule_by_name, LintResult};
+use serde_json::{json, Value};
+use std::io;
+use std::ops::Range;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A formatter which outputs a SARIF 2.1.0 log, which can be uploaded to code scanning services.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SarifFormatter;
+
+impl Formatter for SarifFormatter {
+    fn format(
+        &mut self,
+        results: &[LintResult],
+        files: &FileWalker,
+        writer: &mut dyn WriteColor,
+    ) -> io::Result<()> {
+        let mut rules: Vec<&str> = vec![];
+        let mut sarif_results = vec![];
+
+        for result in results {
+            for diagnostic in sorted_diagnostics(result) {
+                let code = diagnostic.code.as_deref().unwrap_or("rslint");
+                let rule_index = match rules.iter().position(|rule| *rule == code) {
+                    Some(idx) => idx,
+                    None => {
+                        rules.push(code);
+                        rules.len() - 1
+                    }
+                };
+
+                let locations = primary_label(diagnostic)
+                    .map(|label| vec![physical_location(files, label.file_id, &label.range)])
+                    .unwrap_or_default();
+                let related = diagnostic
+                    .labels
+                    .iter()
+                    .filter(|label| label.style == LabelStyle::Secondary)
+                    .map(|label| {
+                        let mut location = physical_location(files, label.file_id, &label.range);
+                        location["message"] = json!({ "text": label.message });
+                        location
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut text = diagnostic.message.clone();
+                for note in &diagnostic.notes {
+                    text.push('\n');
+                    text.push_str(note);
+                }
+
+                let mut sarif_result = json!({
+                    "ruleId": code,
+                    "ruleIndex": rule_index,
+                    "level": level(diagnostic.severity),
+                    "message": { "text": text },
+                    "locations": locations,
+                });
+                if !related.is_empty() {
+                    sarif_result["relatedLocations"] = related.into();
+                }
+                sarif_results.push(sarif_result);
+            }
+        }
+
+        let rules = rules.into_iter().map(rule_descriptor).collect::<Vec<_>>();
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "rslint",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": REPO_LINK,
+                        "rules": rules,
+                    }
+                },
+                "results": sarif_results,
+            }]
+        });
+
+        serde_json::to_writer_pretty(&mut *writer, &log)?;
+        writeln!(writer)
+    }
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "note",
+    }
+}
+
+fn rule_descriptor(code: &str) -> Value {
+    let mut descriptor = json!({ "id": code });
+    if let Some(rule) = get_rule_by_name(code) {
+        descriptor["helpUri"] = format!(
+            "{}/blob/master/docs/rules/{}/{}.md",
+            REPO_LINK,
+            rule.group(),
+            code
+        )
+        .into();
+    }
+    descriptor
+}
+
+fn physical_location(files: &FileWalker, file_id: usize, range: &Range<usize>) -> Value {
+    let start = location(files, file_id, range.start);
+    let end = location(files, file_id, range.end);
+    let name = file_name(files, file_id);
+    let uri = name.trim_start_matches("./").replace('\\', "/");
+
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": uri },
+            "region": {
+                "startLine": start.line_number,
+                "startColumn": start.column_number,
+                "endLine": end.line_number,
+                "endColumn": end.column_number,
+                "byteOffset": range.start,
+                "byteLength": range.end - range.start,
+            }
+        }
+    })
+}
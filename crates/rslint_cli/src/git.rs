@@ -0,0 +1,259 @@
+//! Finding the files and lines changed according to the local git repository.
+
+use crate::{formatters::primary_label, ignore::normalize, FileWalker};
+use hashbrown::HashMap;
+use rslint_core::LintResult;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What files are compared to when finding changed files.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GitRevision {
+    /// The changes since a commit, branch, tag, or any other revision, including uncommitted changes and untracked files.
+    Since(String),
+    /// The changes staged for the next commit.
+    Staged,
+}
+
+/// The files which changed, and the lines of each file which changed.
+#[derive(Debug, Clone, Default)]
+pub struct ChangedFiles {
+    /// The changed files by their absolute path, with the one based line ranges which were added or changed.
+    files: HashMap<PathBuf, Vec<Range<usize>>>,
+}
+
+impl ChangedFiles {
+    /// Ask git which files in the working directory changed, deleted files are not included.
+    pub fn load(revision: &GitRevision) -> io::Result<Self> {
+        let names = git_diff(revision, &["--name-only", "-z"])?;
+        let mut files = names
+            .split('\0')
+            .filter(|name| !name.is_empty())
+            .map(|name| (normalize(Path::new(name)), vec![]))
+            .collect::<HashMap<_, _>>();
+
+        let diff = git_diff(revision, &["-U0", "--no-color", "--no-ext-diff"])?;
+        for (path, lines) in parse_changed_lines(&diff) {
+            if let Some(ranges) = files.get_mut(&normalize(&path)) {
+                *ranges = lines;
+            }
+        }
+
+        // `git diff` does not know about untracked files, every line of them is new
+        if let GitRevision::Since(_) = revision {
+            let untracked = git(&["ls-files", "--others", "--exclude-standard", "-z"])?;
+            let all_lines = 1..usize::MAX;
+            files.extend(
+                untracked
+                    .split('\0')
+                    .filter(|name| !name.is_empty())
+                    .map(|name| (normalize(Path::new(name)), vec![all_lines.clone()])),
+            );
+        }
+        Ok(Self { files })
+    }
+
+    /// Whether a file changed.
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        self.files.contains_key(&normalize(path.as_ref()))
+    }
+
+    /// Whether a one based line of a file was added or changed.
+    pub fn is_changed_line(&self, path: impl AsRef<Path>, line: usize) -> bool {
+        self.files
+            .get(&normalize(path.as_ref()))
+            .map_or(false, |ranges| {
+                ranges.iter().any(|range| range.contains(&line))
+            })
+    }
+
+    /// Remove the diagnostics of rules and directives which are not on changed lines.
+    /// Syntax errors are always kept because they can be caused by changes elsewhere.
+    pub fn restrict_to_changed_lines(&self, results: &mut [LintResult], walker: &FileWalker) {
+        for result in results.iter_mut() {
+            let file = &walker.files[&result.file_id];
+            let path = match file.path.as_ref() {
+                Some(path) => path,
+                None => continue,
+            };
+            let is_changed = |diagnostic: &rslint_core::Diagnostic| {
+                primary_label(diagnostic).map_or(true, |label| {
+                    file.line_index(label.range.start)
+                        .map_or(true, |line| self.is_changed_line(path, line + 1))
+                })
+            };
+
+            for rule_result in result.rule_results.values_mut() {
+                rule_result.diagnostics.retain(is_changed);
+            }
+            result.directive_diagnostics.retain(is_changed);
+        }
+    }
+}
+
+/// Run `git diff` against a revision with extra arguments, returning its output.
+/// Paths are relative to the working directory, and files outside of it are not included.
+fn git_diff(revision: &GitRevision, args: &[&str]) -> io::Result<String> {
+    // the prefixes and the quoting of paths in diffs depend on the config of the user, which the parser can't know
+    let diff = [
+        "-c",
+        "core.quotePath=false",
+        "diff",
+        "--relative",
+        "--diff-filter=d",
+        "--src-prefix=a/",
+        "--dst-prefix=b/",
+    ];
+    let mut args = [&diff, args].concat();
+    match revision {
+        GitRevision::Since(rev) => args.push(rev),
+        GitRevision::Staged => args.push("--cached"),
+    };
+    // make sure the revision is never taken as a path
+    args.push("--");
+    git(&args)
+}
+
+/// Run git with arguments, returning its output or its error message if it failed.
+fn git(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse a diff with no context lines, returning the line ranges which were added or changed in each file.
+fn parse_changed_lines(diff: &str) -> Vec<(PathBuf, Vec<Range<usize>>)> {
+    let mut files: Vec<(PathBuf, Vec<Range<usize>>)> = vec![];
+
+    let mut prev = "";
+    for line in diff.lines() {
+        let header = prev.starts_with("--- ");
+        prev = line;
+
+        // added lines can also start with `+++`, but file headers always come right after the `---` header
+        if let Some(path) = line.strip_prefix("+++ ").filter(|_| header) {
+            let path = unquote(path);
+            let path = path.strip_prefix("b/").unwrap_or(&path);
+            files.push((PathBuf::from(path), vec![]));
+        } else if let Some(hunk) = line.strip_prefix("@@ ") {
+            // a hunk header looks like `@@ -start,count +start,count @@`, counts of one are omitted
+            let added = match hunk.split(' ').find(|part| part.starts_with('+')) {
+                Some(added) => &added[1..],
+                None => continue,
+            };
+            let mut parts = added.split(',').map(|num| num.parse::<usize>().ok());
+            let start = parts.next().flatten();
+            let count = parts.next().unwrap_or(Some(1));
+            if let (Some(start), Some(count), Some((_, ranges))) = (start, count, files.last_mut())
+            {
+                if count > 0 {
+                    ranges.push(start..start + count);
+                }
+            }
+        }
+    }
+    files
+}
+
+/// Unquote a path of a diff header, git quotes paths with special characters like a C string.
+fn unquote(path: &str) -> String {
+    let inner = match path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+    {
+        Some(inner) => inner,
+        None => return path.to_string(),
+    };
+
+    let mut bytes = vec![];
+    let mut chars = inner.bytes();
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match chars.next() {
+            Some(b'a') => bytes.push(7),
+            Some(b'b') => bytes.push(8),
+            Some(b'f') => bytes.push(12),
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'v') => bytes.push(11),
+            // non ASCII bytes are escaped as three octal digits
+            Some(digit @ b'0'..=b'7') => {
+                let octal = std::iter::once(digit)
+                    .chain(chars.by_ref().take(2))
+                    .fold(0u8, |value, digit| {
+                        value.wrapping_mul(8).wrapping_add(digit - b'0')
+                    });
+                bytes.push(octal);
+            }
+            Some(other) => bytes.push(other),
+            None => {}
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_lines() {
+        let diff = "\
+diff --git a/src/a.js b/src/a.js
+index 1234567..89abcde 100644
+--- a/src/a.js
++++ b/src/a.js
+@@ -2 +2 @@ function foo() {
+-  bar;
++  baz;
+@@ -10,0 +11,3 @@ function foo() {
++a;
++b;
++c;
+@@ -20,2 +22,0 @@ function foo() {
+-d;
+-e;
+diff --git a/b.js b/b.js
+new file mode 100644
+--- /dev/null
++++ b/b.js
+@@ -0,0 +1,2 @@
++let a;
++let b;
+";
+        assert_eq!(
+            parse_changed_lines(diff),
+            vec![
+                (PathBuf::from("src/a.js"), vec![2..3, 11..14]),
+                (PathBuf::from("b.js"), vec![1..3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted_paths() {
+        let diff = r#"diff --git "a/caf\303\251 \"x\".js" "b/caf\303\251 \"x\".js"
+--- "a/caf\303\251 \"x\".js"
++++ "b/caf\303\251 \"x\".js"
+@@ -1 +1 @@
+-a;
++b;
+"#;
+        assert_eq!(
+            parse_changed_lines(diff),
+            vec![(PathBuf::from("café \"x\".js"), vec![1..2])]
+        );
+        assert_eq!(unquote("b/plain.js"), "b/plain.js");
+    }
+}
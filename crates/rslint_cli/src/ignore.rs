@@ -0,0 +1,555 @@
+//! Filtering of the files which are linted, using ignore files with gitignore syntax and file extensions.
+
+use crate::{config::Config, lint_warn, ConfigResolver};
+use hashbrown::HashMap;
+use regex::Regex;
+use std::env::current_dir;
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// The name of the ignore file to search for.
+pub const IGNORE_FILE_NAME: &str = ".rslintignore";
+/// Patterns which are ignored unless `--no-ignore` is used.
+const DEFAULT_IGNORED: [&str; 1] = ["node_modules/"];
+/// The extensions of files linted by default.
+const DEFAULT_EXTENSIONS: [&str; 2] = ["js", "mjs"];
+
+/// A single pattern of an ignore file.
+#[derive(Debug, Clone)]
+struct IgnorePattern {
+    regex: Regex,
+    /// Whether the pattern started with `!`, which re-includes paths ignored by previous patterns.
+    negated: bool,
+    /// Whether the pattern ended with `/`, which means it only matches directories.
+    dir_only: bool,
+}
+
+/// A list of patterns in gitignore syntax, such as the patterns of a `.rslintignore` file.
+///
+/// Patterns containing a `/` (other than a trailing one) are anchored to the base directory,
+/// other patterns match a file or directory name at any depth.
+#[derive(Debug, Clone)]
+pub struct IgnorePatterns {
+    /// The directory which patterns are relative to.
+    base: PathBuf,
+    patterns: Vec<IgnorePattern>,
+}
+
+impl IgnorePatterns {
+    /// Parse a list of patterns relative to a directory, one pattern per line.
+    /// Invalid patterns are skipped with a warning.
+    pub fn new<'a>(base: impl AsRef<Path>, lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let patterns = lines
+            .into_iter()
+            .filter_map(|line| {
+                let res = IgnorePattern::parse(line)?;
+                if res.is_none() {
+                    lint_warn!("ignoring invalid ignore pattern '{}'", line.trim());
+                }
+                res
+            })
+            .collect();
+
+        Self {
+            base: normalize(base.as_ref()),
+            patterns,
+        }
+    }
+
+    /// Read an ignore file, patterns in the file are relative to the directory of the file.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = normalize(path.as_ref());
+        let text = read_to_string(&path)?;
+        let base = path.parent().unwrap_or(&path);
+        Ok(Self::new(base, text.lines()))
+    }
+
+    /// Whether a path or one of its parent directories matches these patterns, without being re-included
+    /// by a negated pattern.
+    pub fn is_match(&self, path: impl AsRef<Path>, is_dir: bool) -> bool {
+        let path = normalize(path.as_ref());
+        path.ancestors()
+            .skip(1)
+            .any(|dir| self.matched(dir, true) == Some(true))
+            || self.matched(&path, is_dir) == Some(true)
+    }
+
+    /// Whether a path is ignored by these patterns, `Some(false)` means it was re-included by a negated pattern
+    /// and `None` means no pattern matched it. The path must be normalized.
+    fn matched(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if relative.is_empty() {
+            return None;
+        }
+
+        self.patterns
+            .iter()
+            .rev()
+            .find(|pattern| (is_dir || !pattern.dir_only) && pattern.regex.is_match(&relative))
+            .map(|pattern| !pattern.negated)
+    }
+}
+
+impl IgnorePattern {
+    /// Parse a line of an ignore file, returns `None` for blank lines and comments,
+    /// and `Some(None)` if the pattern is invalid.
+    fn parse(line: &str) -> Option<Option<Self>> {
+        let mut pattern = line.trim_end();
+        // trailing spaces are kept if they are escaped
+        if pattern.ends_with('\\') && line.len() > pattern.len() {
+            pattern = &line[..pattern.len() + 1];
+        }
+        if pattern.is_empty() || pattern.starts_with('#') {
+            return None;
+        }
+
+        let negated = pattern.starts_with('!');
+        if negated {
+            pattern = &pattern[1..];
+        }
+        let dir_only = pattern.ends_with('/') && !pattern.ends_with("\\/");
+        if dir_only {
+            pattern = &pattern[..pattern.len() - 1];
+        }
+        let anchored = pattern.contains('/');
+        pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+        let mut regex = String::from("^");
+        if !anchored {
+            regex.push_str("(?:.*/)?");
+        }
+        let segments = pattern.split('/').collect::<Vec<_>>();
+        for (idx, segment) in segments.iter().enumerate() {
+            let last = idx == segments.len() - 1;
+            if *segment == "**" {
+                regex.push_str(if last { ".*" } else { "(?:.*/)?" });
+                continue;
+            }
+            match translate_segment(segment) {
+                Some(translated) => regex.push_str(&translated),
+                None => return Some(None),
+            }
+            if !last {
+                regex.push('/');
+            }
+        }
+        regex.push('$');
+
+        Some(Regex::new(&regex).ok().map(|regex| Self {
+            regex,
+            negated,
+            dir_only,
+        }))
+    }
+}
+
+/// Translate a single path segment of a pattern to a regex, `*` and `?` never match `/`.
+fn translate_segment(segment: &str) -> Option<String> {
+    let mut regex = String::new();
+    let mut chars = segment.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => regex.push_str(&regex::escape(&chars.next()?.to_string())),
+            '*' => {
+                while chars.peek() == Some(&'*') {
+                    chars.next();
+                }
+                regex.push_str("[^/]*");
+            }
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let rest = chars.clone().collect::<String>();
+                let negated = rest.starts_with('!') || rest.starts_with('^');
+                let content_start = if negated { 1 } else { 0 };
+                // a `]` right after the opening bracket is part of the class
+                let end = rest[content_start..]
+                    .char_indices()
+                    .find(|(idx, c)| *c == ']' && *idx != 0)
+                    .map(|(idx, _)| idx + content_start);
+
+                match end {
+                    Some(end) => {
+                        regex.push('[');
+                        if negated {
+                            regex.push('^');
+                        }
+                        for c in rest[content_start..end].chars() {
+                            if c == '-' {
+                                regex.push(c);
+                            } else {
+                                regex.push_str(&regex::escape(&c.to_string()));
+                            }
+                        }
+                        regex.push(']');
+                        for _ in rest[..=end].chars() {
+                            chars.next();
+                        }
+                    }
+                    None => regex.push_str("\\["),
+                }
+            }
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    Some(regex)
+}
+
+/// Decides which files are linted based on ignore patterns and file extensions.
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    /// Ignore patterns, a later source of patterns takes precedence over earlier ones.
+    pub ignores: Vec<IgnorePatterns>,
+    /// The extensions of files to lint, without a leading `.`.
+    pub extensions: Vec<String>,
+    /// The paths of the configs the filter was made from.
+    configs: Vec<PathBuf>,
+    /// Whether ignore patterns are disabled with `--no-ignore`.
+    no_ignore: bool,
+    /// The index in `ignores` of the patterns of ignore files, which take precedence over the patterns of configs.
+    ignore_files_start: usize,
+}
+
+impl Default for FileFilter {
+    /// Ignore `node_modules` and lint `.js` and `.mjs` files.
+    fn default() -> Self {
+        let cwd = current_dir().unwrap_or_default();
+        Self {
+            ignores: vec![IgnorePatterns::new(cwd, DEFAULT_IGNORED.iter().copied())],
+            extensions: DEFAULT_EXTENSIONS
+                .iter()
+                .map(|ext| ext.to_string())
+                .collect(),
+            configs: vec![],
+            no_ignore: false,
+            ignore_files_start: 1,
+        }
+    }
+}
+
+impl FileFilter {
+    /// Make a filter from the configs of the linting directory, from the outermost to the nearest one,
+    /// and the command line options.
+    ///
+    /// Patterns are taken from, in order of precedence: `.rslintignore` (or the file given by `--ignore-path`),
+    /// the `ignore` key of each config, `.gitignore` if `gitignore` is enabled in the config, and the defaults.
+    /// `--no-ignore` disables all of them. A missing `.rslintignore` or `.gitignore` is not an error,
+    /// but a missing `--ignore-path` file is.
+    pub fn from_configs(
+        configs: &[Arc<Config>],
+        no_ignore: bool,
+        ignore_path: Option<&Path>,
+    ) -> io::Result<Self> {
+        let cwd = current_dir()?;
+        let mut filter = Self {
+            configs: configs.iter().filter_map(|cfg| cfg.path.clone()).collect(),
+            no_ignore,
+            ..Self::default()
+        };
+        filter.set_extensions(configs);
+
+        if no_ignore {
+            filter.ignores.clear();
+            filter.ignore_files_start = 0;
+            return Ok(filter);
+        }
+
+        if configs
+            .iter()
+            .rev()
+            .find_map(|cfg| cfg.gitignore)
+            .unwrap_or(false)
+        {
+            if let Ok(patterns) = IgnorePatterns::from_file(cwd.join(".gitignore")) {
+                filter.ignores.push(patterns);
+            }
+        }
+        for cfg in configs {
+            filter.ignores.push(IgnorePatterns::new(
+                cfg.dir().unwrap_or(&cwd),
+                cfg.ignore.iter().map(|pattern| pattern.as_str()),
+            ));
+        }
+
+        filter.ignore_files_start = filter.ignores.len();
+        if let Some(path) = ignore_path {
+            filter.ignores.push(IgnorePatterns::from_file(path)?);
+        } else if let Ok(patterns) = IgnorePatterns::from_file(cwd.join(IGNORE_FILE_NAME)) {
+            filter.ignores.push(patterns);
+        }
+
+        Ok(filter)
+    }
+
+    /// The filter of a directory whose configs are `configs`, configs which are not part of the configs this
+    /// filter was made from are nested inside of the linting directory. Their `ignore` patterns are added
+    /// to the patterns of this filter, and their `extensions` take precedence over the extensions of this filter.
+    pub fn with_nested_configs(&self, configs: &[Arc<Config>]) -> Self {
+        let nested = configs
+            .iter()
+            .filter(|cfg| matches!(&cfg.path, Some(path) if !self.configs.contains(path)))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut filter = self.clone();
+        filter.set_extensions(&nested);
+        if !self.no_ignore {
+            for (idx, cfg) in nested.iter().enumerate() {
+                let patterns = IgnorePatterns::new(
+                    cfg.dir().unwrap_or_else(|| Path::new("")),
+                    cfg.ignore.iter().map(|pattern| pattern.as_str()),
+                );
+                filter
+                    .ignores
+                    .insert(self.ignore_files_start + idx, patterns);
+            }
+            filter.ignore_files_start += nested.len();
+        }
+        filter
+            .configs
+            .extend(nested.iter().filter_map(|cfg| cfg.path.clone()));
+        filter
+    }
+
+    /// Use the extensions of the nearest config which has them.
+    fn set_extensions(&mut self, configs: &[Arc<Config>]) {
+        if let Some(extensions) = configs.iter().rev().find_map(|cfg| cfg.extensions.as_ref()) {
+            self.extensions = extensions
+                .iter()
+                .map(|ext| ext.trim_start_matches('.').to_string())
+                .collect();
+        }
+    }
+
+    /// Whether a path is ignored, either by itself or because one of its parent directories is ignored.
+    pub fn is_ignored(&self, path: impl AsRef<Path>, is_dir: bool) -> bool {
+        let path = normalize(path.as_ref());
+        let ancestors = path.ancestors().skip(1).collect::<Vec<_>>();
+
+        ancestors
+            .into_iter()
+            .rev()
+            .any(|dir| self.matches(dir, true))
+            || self.matches(&path, is_dir)
+    }
+
+    /// Whether a path is ignored, without checking its parent directories.
+    pub(crate) fn is_ignored_entry(&self, path: impl AsRef<Path>, is_dir: bool) -> bool {
+        self.matches(&normalize(path.as_ref()), is_dir)
+    }
+
+    /// Whether a file has one of the linted extensions.
+    pub fn has_linted_extension(&self, path: impl AsRef<Path>) -> bool {
+        path.as_ref().extension().map_or(false, |ext| {
+            self.extensions
+                .iter()
+                .any(|linted| linted.as_str() == ext.to_string_lossy())
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        self.ignores
+            .iter()
+            .rev()
+            .find_map(|patterns| patterns.matched(path, is_dir))
+            .unwrap_or(false)
+    }
+}
+
+/// The filters of the directories found while walking files, which apply the configs nested inside of the
+/// linting directory on top of the filter of the linting directory.
+pub struct DirFilters<'a> {
+    filter: &'a FileFilter,
+    resolver: &'a mut ConfigResolver,
+    /// The index in `filters` of the filter of each directory, `None` if it is the filter of the linting directory.
+    dirs: HashMap<PathBuf, Option<usize>>,
+    /// The filters of directories with nested configs, with the paths of the configs of the directories.
+    filters: Vec<(Vec<PathBuf>, FileFilter)>,
+}
+
+impl<'a> DirFilters<'a> {
+    pub fn new(filter: &'a FileFilter, resolver: &'a mut ConfigResolver) -> Self {
+        Self {
+            filter,
+            resolver,
+            dirs: HashMap::new(),
+            filters: vec![],
+        }
+    }
+
+    /// The filter of the files inside of a directory. Directories with an invalid config use the filter of
+    /// the linting directory, the config error is reported when the config of the files is resolved.
+    pub fn get(&mut self, dir: impl AsRef<Path>) -> &FileFilter {
+        let dir = dir.as_ref();
+        let idx = match self.dirs.get(dir) {
+            Some(idx) => *idx,
+            None => {
+                let idx = self.filter_of(dir);
+                self.dirs.insert(dir.to_owned(), idx);
+                idx
+            }
+        };
+        match idx {
+            Some(idx) => &self.filters[idx].1,
+            None => self.filter,
+        }
+    }
+
+    fn filter_of(&mut self, dir: &Path) -> Option<usize> {
+        let configs = self.resolver.chain(dir).unwrap_or_default();
+        let paths = configs
+            .iter()
+            .filter_map(|cfg| cfg.path.clone())
+            .collect::<Vec<_>>();
+        if paths.iter().all(|path| self.filter.configs.contains(path)) {
+            return None;
+        }
+        // most directories share the configs of their parent directory
+        let existing = self.filters.iter().position(|(key, _)| *key == paths);
+        Some(existing.unwrap_or_else(|| {
+            let filter = self.filter.with_nested_configs(&configs);
+            self.filters.push((paths, filter));
+            self.filters.len() - 1
+        }))
+    }
+
+    /// Whether a path is ignored by the filter of its directory.
+    pub fn is_ignored(&mut self, path: impl AsRef<Path>, is_dir: bool) -> bool {
+        let path = normalize(path.as_ref());
+        let dir = path.parent().unwrap_or(&path).to_owned();
+        self.get(dir).is_ignored(&path, is_dir)
+    }
+}
+
+/// Make a path absolute and remove `.` and `..` components, without touching the file system.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let absolute = current_dir().unwrap_or_default().join(path);
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(patterns: &str) -> FileFilter {
+        FileFilter {
+            ignores: vec![IgnorePatterns::new("/project", patterns.lines())],
+            extensions: vec!["js".to_string()],
+            ..FileFilter::default()
+        }
+    }
+
+    #[test]
+    fn unanchored_patterns() {
+        let filter = filter("# a comment\n\n*.min.js\nbuild/\nfoo.js");
+        assert!(filter.is_ignored("/project/a.min.js", false));
+        assert!(filter.is_ignored("/project/src/lib/a.min.js", false));
+        assert!(filter.is_ignored("/project/src/build", true));
+        assert!(filter.is_ignored("/project/src/build/a.js", false));
+        assert!(filter.is_ignored("/project/nested/foo.js", false));
+        assert!(!filter.is_ignored("/project/build", false));
+        assert!(!filter.is_ignored("/project/src/a.js", false));
+        assert!(!filter.is_ignored("/other/foo.js", false));
+    }
+
+    #[test]
+    fn anchored_patterns() {
+        let filter = filter("/dist\nsrc/*.js\nlib/**/gen\n**/tmp/*\nvendor/**");
+        assert!(filter.is_ignored("/project/dist/a.js", false));
+        assert!(!filter.is_ignored("/project/src/dist/a.js", false));
+        assert!(filter.is_ignored("/project/src/a.js", false));
+        assert!(!filter.is_ignored("/project/src/nested/a.js", false));
+        assert!(filter.is_ignored("/project/lib/gen", false));
+        assert!(filter.is_ignored("/project/lib/a/b/gen/c.js", false));
+        assert!(filter.is_ignored("/project/a/tmp/b.js", false));
+        assert!(filter.is_ignored("/project/vendor/a/b.js", false));
+        assert!(!filter.is_ignored("/project/vendor", true));
+    }
+
+    #[test]
+    fn negated_patterns() {
+        let filter = filter("*.js\n!keep.js\nignored/\n!ignored/keep.js");
+        assert!(filter.is_ignored("/project/a.js", false));
+        assert!(!filter.is_ignored("/project/keep.js", false));
+        assert!(!filter.is_ignored("/project/src/keep.js", false));
+        // files in an ignored directory can't be re-included
+        assert!(filter.is_ignored("/project/ignored/keep.js", false));
+    }
+
+    #[test]
+    fn character_classes_and_escapes() {
+        let filter = filter("file[0-9].js\n[!a]b.js\n\\#hash.js\n\\!bang.js\na?c.js");
+        assert!(filter.is_ignored("/project/file1.js", false));
+        assert!(!filter.is_ignored("/project/filex.js", false));
+        assert!(filter.is_ignored("/project/cb.js", false));
+        assert!(!filter.is_ignored("/project/ab.js", false));
+        assert!(filter.is_ignored("/project/#hash.js", false));
+        assert!(filter.is_ignored("/project/!bang.js", false));
+        assert!(filter.is_ignored("/project/abc.js", false));
+        assert!(!filter.is_ignored("/project/a/c.js", false));
+    }
+
+    #[test]
+    fn later_sources_take_precedence() {
+        let mut filter = filter("*.js");
+        filter
+            .ignores
+            .push(IgnorePatterns::new("/project/src", vec!["!a.js"]));
+        assert!(filter.is_ignored("/project/a.js", false));
+        assert!(!filter.is_ignored("/project/src/a.js", false));
+    }
+
+    #[test]
+    fn extensions() {
+        let filter = filter("");
+        assert!(filter.has_linted_extension("a.js"));
+        assert!(!filter.has_linted_extension("a.mjs"));
+        assert!(!filter.has_linted_extension("js"));
+    }
+
+    #[test]
+    fn nested_configs() {
+        let root = std::env::temp_dir().join("rslint-ignore-nested");
+        let _ = std::fs::remove_dir_all(&root);
+        for (path, content) in &[
+            ("rslintrc.toml", "root = true"),
+            (
+                "pkg/rslintrc.toml",
+                "ignore = [\"dist/\"]\nextensions = [\"js\", \"ts\"]",
+            ),
+        ] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let mut resolver = ConfigResolver::new();
+        let configs = resolver.chain(&root).unwrap();
+        let filter = FileFilter::from_configs(&configs, false, None).unwrap();
+        let mut filters = DirFilters::new(&filter, &mut resolver);
+
+        assert!(filters.is_ignored(root.join("pkg/dist/a.js"), false));
+        assert!(filters.is_ignored(root.join("pkg/dist"), true));
+        assert!(!filters.is_ignored(root.join("pkg/src/a.js"), false));
+        assert!(!filters.is_ignored(root.join("dist/a.js"), false));
+        assert!(filters.get(root.join("pkg")).has_linted_extension("a.ts"));
+        assert!(!filters.get(&root).has_linted_extension("a.ts"));
+    }
+}
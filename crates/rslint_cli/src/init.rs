@@ -0,0 +1,231 @@
+//! Generating a starter config with `rslint init`.
+
+use crate::{inline_toml, lint_err, lint_note, rule_config, ExitCode, CONFIG_NAME, REPO_LINK};
+use rslint_core::{CstRuleStore, GROUP_NAMES};
+use std::fmt::Write;
+use std::io::{self, BufRead, Write as _};
+use std::path::Path;
+use toml::Value;
+
+/// The module systems which can be chosen with `rslint init --modules`.
+pub const MODULE_SYSTEMS: [&str; 2] = ["esm", "commonjs"];
+
+/// Write a starter config to the working directory, asking for the environments and the module system
+/// if neither is given and stdin is a terminal.
+pub fn init(mut envs: Vec<String>, mut modules: Option<String>, force: bool) -> ExitCode {
+    let path = Path::new(CONFIG_NAME);
+    if path.exists() && !force {
+        lint_err!(
+            "`{}` already exists, use `--force` to overwrite it",
+            CONFIG_NAME
+        );
+        return ExitCode::UsageError;
+    }
+
+    if envs.is_empty() && modules.is_none() && atty::is(atty::Stream::Stdin) {
+        match ask_environments() {
+            Ok((asked_envs, asked_modules)) => {
+                envs = asked_envs;
+                modules = asked_modules;
+            }
+            Err(err) => {
+                lint_err!("failed to read from stdin: {}", err);
+                return ExitCode::UsageError;
+            }
+        }
+    }
+
+    if let Err(err) = std::fs::write(path, starter_config(&envs, modules.as_deref())) {
+        lint_err!("failed to write `{}`: {}", CONFIG_NAME, err);
+        return ExitCode::UsageError;
+    }
+    lint_note!("Wrote `{}`", CONFIG_NAME);
+    ExitCode::Success
+}
+
+/// Ask where the code runs and which module system it uses.
+fn ask_environments() -> io::Result<(Vec<String>, Option<String>)> {
+    let envs = match ask(
+        "Where does your code run?",
+        &["browser", "node", "both", "none"],
+    )?
+    .as_str()
+    {
+        "both" => vec!["browser".to_string(), "node".to_string()],
+        "none" => vec![],
+        env => vec![env.to_string()],
+    };
+    let modules = match ask(
+        "Which modules does your code use?",
+        &["esm", "commonjs", "none"],
+    )?
+    .as_str()
+    {
+        "none" => None,
+        modules => Some(modules.to_string()),
+    };
+    Ok((envs, modules))
+}
+
+/// Ask a question until the answer is one of the choices, the first choice is the default.
+fn ask(question: &str, choices: &[&str]) -> io::Result<String> {
+    let stdin = io::stdin();
+    let default = choices[0];
+    loop {
+        eprint!("{} ({}) [{}]: ", question, choices.join(", "), default);
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        if stdin.lock().read_line(&mut answer)? == 0 {
+            return Ok(default.to_string());
+        }
+        let answer = answer.trim().to_ascii_lowercase();
+        if answer.is_empty() {
+            return Ok(default.to_string());
+        }
+        if choices.contains(&answer.as_str()) {
+            return Ok(answer);
+        }
+    }
+}
+
+/// A commented config which enables the builtin groups and lists every builtin rule with its default config.
+pub fn starter_config(envs: &[String], modules: Option<&str>) -> String {
+    let mut config = String::new();
+    let store = CstRuleStore::new().builtins();
+
+    writeln!(
+        config,
+        "# The config of RSLint, see {}/blob/master/docs/user/config.md for every option.",
+        REPO_LINK
+    )
+    .unwrap();
+    if modules == Some("esm") {
+        writeln!(
+            config,
+            "\n# Parse `.js` files as modules, only `.mjs` files are modules by default."
+        )
+        .unwrap();
+        writeln!(config, "modules = \"esm\"").unwrap();
+    }
+    writeln!(config, "\n[rules]").unwrap();
+    writeln!(
+        config,
+        "# Groups of rules to run as errors, the builtin groups are: {}",
+        GROUP_NAMES.join(", ")
+    )
+    .unwrap();
+    writeln!(
+        config,
+        "groups = {}",
+        inline_toml(&Value::try_from(GROUP_NAMES).unwrap())
+    )
+    .unwrap();
+    writeln!(
+        config,
+        "# Rules which are never run, e.g. `allowed = [\"no-empty\"]`."
+    )
+    .unwrap();
+    writeln!(config, "allowed = []").unwrap();
+
+    writeln!(
+        config,
+        "\n# Rules to run as errors with their config, the commented out configs are the defaults."
+    )
+    .unwrap();
+    writeln!(config, "[rules.errors]").unwrap();
+    for group in GROUP_NAMES.iter() {
+        writeln!(config, "# Rules of the `{}` group:", group).unwrap();
+        let mut rules = store
+            .rules
+            .iter()
+            .filter(|rule| rule.group() == *group)
+            .collect::<Vec<_>>();
+        rules.sort_by_key(|rule| rule.name());
+        for rule in rules {
+            let value = Value::try_from(rule_config(&**rule))
+                .unwrap_or_else(|_| Value::Table(Default::default()));
+            writeln!(config, "# {} = {}", rule.name(), inline_toml(&value)).unwrap();
+        }
+    }
+
+    writeln!(config, "\n# Rules to run as warnings with their config.").unwrap();
+    writeln!(config, "[rules.warnings]").unwrap();
+
+    writeln!(
+        config,
+        "\n# The level of rules, one of `off`, `info`, `warn`, or `error`, e.g. `no-empty = \"info\"`."
+    )
+    .unwrap();
+    writeln!(config, "[rules.levels]").unwrap();
+
+    writeln!(
+        config,
+        "\n# Environments which provide globals, `es2021` is enabled by default."
+    )
+    .unwrap();
+    writeln!(config, "[env]").unwrap();
+    for env in envs {
+        writeln!(config, "{} = true", env).unwrap();
+    }
+    if modules == Some("commonjs") && !envs.iter().any(|env| env == "node") {
+        writeln!(config, "commonjs = true").unwrap();
+    }
+
+    writeln!(config, "\n# Extra globals, e.g. `jQuery = \"readonly\"`.").unwrap();
+    writeln!(config, "[globals]").unwrap();
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, session::Session, Options};
+    use structopt::StructOpt;
+
+    #[test]
+    fn starter_config_is_valid() {
+        let config = starter_config(&["browser".to_string()], Some("commonjs"));
+        let parsed = toml::from_str::<Config>(&config).unwrap();
+        assert_eq!(parsed.env.get("browser"), Some(&true));
+        assert_eq!(parsed.env.get("commonjs"), Some(&true));
+
+        // the commented out defaults have to be valid configs too
+        let uncommented = config
+            .lines()
+            .map(|line| match line.strip_prefix("# ") {
+                Some(rule) if rule.contains(" = ") && !rule.contains('`') => rule,
+                _ => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let rules = toml::from_str::<Config>(&uncommented)
+            .unwrap()
+            .rules
+            .unwrap();
+        // the rules of the enabled groups are listed again
+        assert_eq!(
+            rules.error_rule_names().count(),
+            CstRuleStore::new().builtins().rules.len() + rules.grouped_rules().count()
+        );
+    }
+
+    #[test]
+    fn esm_starter_config() {
+        let root = std::env::temp_dir().join("rslint-init-esm");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join(CONFIG_NAME), starter_config(&[], Some("esm"))).unwrap();
+        let path = root.join("index.js");
+        std::fs::write(&path, "import foo from \"foo\";\nexport default foo;\n").unwrap();
+
+        let options = Options::from_iter(&["rslint", path.to_str().unwrap()]);
+        let session = Session::load(&options).unwrap();
+        let (results, _, unlintable) =
+            session
+                .rules
+                .lint(&session.walker, &session.ids(), false, false);
+        assert!(!unlintable);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].parser_diagnostics.is_empty());
+    }
+}
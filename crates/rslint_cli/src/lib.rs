@@ -1,24 +1,52 @@
+mod baseline;
+mod cache;
 mod cli;
 mod config;
 mod files;
+mod formatters;
+mod git;
+mod ignore;
+mod init;
 mod panic_hook;
+mod print_config;
+mod rules;
+mod schema;
+mod session;
+mod watch;
 
-pub use self::{cli::ExplanationRunner, config::*, files::*, panic_hook::*};
+pub use self::{
+    baseline::*,
+    cache::*,
+    cli::{ExplanationRunner, Options, SubCommand},
+    config::*,
+    files::*,
+    formatters::*,
+    git::*,
+    ignore::*,
+    init::*,
+    panic_hook::*,
+    print_config::*,
+    rules::*,
+    schema::*,
+};
 pub use rslint_core::{Diagnostic, DiagnosticBuilder, Outcome};
 
 use codespan_reporting::diagnostic::Severity;
+use codespan_reporting::files::{Files, SimpleFiles};
 use codespan_reporting::term::Config;
 use codespan_reporting::term::{
     emit,
     termcolor::{self, ColorChoice, StandardStream},
 };
-use rayon::prelude::*;
-use rslint_core::{lint_file, CstRuleStore, RuleLevel};
+use rslint_core::LintResult;
+use session::Session;
+use std::fs::write;
 
-pub(crate) const DOCS_LINK_BASE: &str =
-    "https://raw.githubusercontent.com/RDambrosio016/RSLint/master/docs/rules";
 pub(crate) const REPO_LINK: &str = "https://github.com/RDambrosio016/RSLint";
 
+/// The name of source read from stdin if `--stdin-filename` is not given.
+pub const STDIN_NAME: &str = "<stdin>";
+
 pub fn codespan_config() -> Config {
     let mut base = Config::default();
     base.chars.multi_top_left = '┌';
@@ -26,86 +54,112 @@ pub fn codespan_config() -> Config {
     base
 }
 
-pub fn run(glob: String, verbose: bool) {
-    let res = glob::glob(&glob);
-    if let Err(err) = res {
-        lint_err!("Invalid glob pattern: {}", err);
-        return;
+/// The exit codes of the linter process.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ExitCode {
+    /// No errors were found, and there were not more warnings than allowed by `--max-warnings`.
+    Success = 0,
+    /// Errors were found, or there were more warnings than allowed by `--max-warnings`.
+    LintErrors = 1,
+    /// The config or the command line arguments are invalid.
+    UsageError = 2,
+    /// The linter panicked.
+    Panic = 3,
+}
+
+impl ExitCode {
+    /// Exit the process with this code.
+    pub fn exit(self) -> ! {
+        std::process::exit(self as i32)
     }
+}
 
-    let handle = config::Config::new_threaded();
-    let walker = FileWalker::from_glob(res.unwrap());
-    let joined = handle.join();
-
-    let config = if let Ok(Some(Err(err))) = joined.as_ref() {
-        // this is a bit of a hack. we should do this in a better way in the future
-        // toml also seems to give incorrect column numbers so we can't use it currently
-        let regex = regex::Regex::new(r"\. did you mean '(.*?)'\?").unwrap();
-        let location_regex = regex::Regex::new(r"at line \d+").unwrap();
-        let mut msg = err
-            .to_string()
-            .split_at(location_regex.find(&err.to_string()).unwrap().range().start)
-            .0
-            .to_string();
-        let old = msg.clone();
-
-        let diagnostic = if let Some(found) = regex.find(&old) {
-            msg.replace_range(found.range(), "");
-            DiagnosticBuilder::error(0, "config", &msg).note(format!(
-                "help: did you mean '{}'?",
-                regex.captures(&old).unwrap().get(1).unwrap().as_str()
-            ))
-        } else {
-            DiagnosticBuilder::error(0, "config", msg)
-        };
+/// Lint the files matched by the options and report the results, returning the code the process should exit with.
+pub fn run(options: Options) -> ExitCode {
+    if let Some(path) = options.print_config.as_ref() {
+        return print_config(path, options.format);
+    }
+    if options.watch {
+        return watch::watch(&options);
+    }
 
-        return emit_diagnostic(diagnostic, &FileWalker::empty());
-    } else {
-        joined.unwrap().map(|res| res.unwrap())
+    let mut session = match Session::load(&options) {
+        Ok(session) => session,
+        Err(code) => return code,
     };
-
-    let store = if let Some(cfg) = config.as_ref().and_then(|cfg| cfg.rules.as_ref()) {
-        cfg.store()
+    let fix = options.fix || options.fix_dry_run;
+    let mut cache = if options.cache {
+        Some(Cache::load(CACHE_FILE_NAME))
     } else {
-        CstRuleStore::new().builtins()
+        None
     };
 
-    if walker.files.is_empty() {
-        lint_err!("No matching files found");
-        return;
+    // files which did not change since they were cached are not linted again
+    let mut ids = session.ids();
+    let mut cached = vec![];
+    if let Some(cache) = cache.as_ref() {
+        let (walker, rules) = (&session.walker, &session.rules);
+        ids.retain(|id| {
+            let file = &walker.files[id];
+            let hash = rules.hash(file, options.verbose);
+            match cache.get(file, hash, rules.store(*id), fix) {
+                Some(result) => cached.push(result),
+                None => return true,
+            }
+            false
+        });
     }
 
-    let mut results = walker
-        .files
-        .par_iter()
-        .map(|(id, file)| {
-            lint_file(
-                *id,
-                &file.source,
-                file.kind == JsFileKind::Module,
-                &store,
-                verbose,
-            )
-        })
-        .filter_map(|res| {
-            if let Err(diagnostic) = res {
-                emit_diagnostic(diagnostic, &walker);
-                None
-            } else {
-                res.ok()
-            }
-        })
-        .collect::<Vec<_>>();
-
-    // Map each diagnostic to the correct level according to configured rule level
-    for result in results.iter_mut() {
-        for (rule_name, diagnostics) in result.rule_diagnostics.iter_mut() {
-            if let Some(conf) = config.as_ref().and_then(|cfg| cfg.rules.as_ref()) {
-                remap_diagnostics_to_level(diagnostics, conf.rule_level_by_name(rule_name));
-            }
+    let (mut results, fixed_files, unlintable) =
+        session
+            .rules
+            .lint(&session.walker, &ids, fix, options.verbose);
+
+    if fix {
+        apply_fixed_files(&mut session.walker, fixed_files, options.fix_dry_run);
+    }
+    if let Some(cache) = cache.as_mut() {
+        for result in &results {
+            let file = &session.walker.files[&result.file_id];
+            cache.insert(file, session.rules.hash(file, options.verbose), result);
+        }
+        if let Err(err) = cache.save() {
+            lint_err!("failed to write the cache `{}`: {}", CACHE_FILE_NAME, err);
         }
     }
 
+    results.extend(cached);
+    session.filter_results(&mut results, &options);
+
+    if let Some(path) = options.write_baseline.as_ref() {
+        let baseline = Baseline::new(&results, &session.walker);
+        if let Err(err) = baseline.save(path) {
+            lint_err!("failed to write the baseline `{}`: {}", path.display(), err);
+            return ExitCode::UsageError;
+        }
+        lint_note!(
+            "Wrote {} problems to the baseline `{}`",
+            baseline.len(),
+            path.display()
+        );
+        return ExitCode::Success;
+    }
+    report(&mut results, &session.walker, &options, unlintable)
+}
+
+/// Report the results of linting with the format of the options, returning the code the process should exit with.
+pub(crate) fn report(
+    results: &mut [LintResult],
+    walker: &FileWalker,
+    options: &Options,
+    unlintable: bool,
+) -> ExitCode {
+    let Options {
+        format,
+        max_warnings,
+        ..
+    } = *options;
+
     let failures = results
         .iter()
         .filter(|res| res.outcome() == Outcome::Failure)
@@ -120,22 +174,87 @@ pub fn run(glob: String, verbose: bool) {
         .count();
 
     let overall = Outcome::merge(results.iter().map(|res| res.outcome()));
+    let warning_count = results
+        .iter()
+        .flat_map(|res| res.diagnostics())
+        .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+        .count();
 
-    for result in results.into_iter() {
-        for diagnostic in result.diagnostics() {
-            emit(
-                &mut StandardStream::stderr(ColorChoice::Always),
-                &codespan_config(),
-                &walker,
-                diagnostic,
-            )
-            .expect("Failed to throw diagnostic");
+    // files are linted in parallel, sort the results so the output is stable between runs
+    results.sort_by_cached_key(|res| walker.name(res.file_id).map(|name| name.into_owned()));
+
+    let mut formatter = format.formatter();
+    let res = if format.is_machine_readable() {
+        formatter.format(
+            results,
+            walker,
+            &mut StandardStream::stdout(ColorChoice::Never),
+        )
+    } else {
+        formatter.format(
+            results,
+            walker,
+            &mut StandardStream::stderr(ColorChoice::Always),
+        )
+    };
+    if let Err(err) = res {
+        lint_err!("failed to write the lint results: {}", err);
+    }
+
+    if !format.is_machine_readable() {
+        output_overall(failures, warnings, successes);
+        if overall == Outcome::Failure {
+            println!("\nhelp: for more information about the errors try the explain command: `rslint explain <rules>`");
         }
     }
 
-    output_overall(failures, warnings, successes);
-    if overall == Outcome::Failure {
-        println!("\nhelp: for more information about the errors try the explain command: `rslint explain <rules>`");
+    let too_many_warnings = max_warnings.map_or(false, |max| warning_count > max);
+    if too_many_warnings {
+        lint_err!(
+            "found {} warnings, which is more than the maximum of {}",
+            warning_count,
+            max_warnings.unwrap()
+        );
+    }
+
+    if overall == Outcome::Failure || too_many_warnings || unlintable {
+        ExitCode::LintErrors
+    } else {
+        ExitCode::Success
+    }
+}
+
+/// Update the walker with the sources of fixed files so diagnostics are rendered against the fixed source,
+/// then write the fixed files to disk if this is not a dry run.
+fn apply_fixed_files(walker: &mut FileWalker, fixed_files: Vec<(usize, String, usize)>, dry: bool) {
+    let mut total = 0;
+    let file_count = fixed_files.len();
+
+    for (id, fixed, count) in fixed_files {
+        let file = walker.files.get_mut(&id).unwrap();
+        if !dry {
+            if let Some(path) = file.path.as_ref() {
+                if let Err(err) = write(path, &fixed) {
+                    lint_err!("failed to write fixed file `{}`: {}", path.display(), err);
+                    continue;
+                }
+            }
+        }
+        file.update_src(fixed);
+        total += count;
+    }
+
+    if total == 0 {
+        return;
+    }
+    if dry {
+        lint_note!(
+            "{} fixes would be applied to {} files (dry run)",
+            total,
+            file_count
+        );
+    } else {
+        lint_note!("applied {} fixes to {} files", total, file_count);
     }
 }
 
@@ -168,18 +287,27 @@ fn output_overall(failures: usize, warnings: usize, successes: usize) {
     writeln!(&mut stdout, " success").unwrap();
 }
 
-/// Remap each error diagnostic to a warning diagnostic based on the rule's level.
-/// this leaves warnings untouched because rules should be able to emit errors and warnings for context without
-/// the warnings being remapped to errors.
-pub fn remap_diagnostics_to_level(diagnostics: &mut Vec<Diagnostic>, level: RuleLevel) {
-    for diagnostic in diagnostics.iter_mut() {
-        match diagnostic.severity {
-            Severity::Error | Severity::Bug if level == RuleLevel::Warning => {
-                diagnostic.severity = Severity::Warning
-            }
-            _ => {}
-        }
+/// Emit an error for an invalid config file, pointing at the offending key or value if it is known.
+pub fn emit_config_error(err: &ConfigError) {
+    let mut files = SimpleFiles::new();
+    let id = files.add(err.path.display().to_string(), err.source.as_str());
+
+    let mut diagnostic = DiagnosticBuilder::error(id, "config", &err.message);
+    match err.span.clone() {
+        Some(span) => diagnostic = diagnostic.primary(span, ""),
+        None => diagnostic = diagnostic.note(format!("note: in `{}`", err.path.display())),
     }
+    if let Some(help) = &err.help {
+        diagnostic = diagnostic.note(format!("help: {}", help));
+    }
+
+    emit(
+        &mut termcolor::StandardStream::stderr(ColorChoice::Always),
+        &crate::codespan_config(),
+        &files,
+        &diagnostic.into(),
+    )
+    .expect("Failed to throw linter diagnostic");
 }
 
 pub fn emit_diagnostic(diagnostic: impl Into<Diagnostic>, walker: &FileWalker) {
@@ -236,3 +364,76 @@ macro_rules! lint_note {
         $crate::lint_diagnostic!(note_diagnostic, $($format_args)*);
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_core::{groups::errors::NoEmpty, lint_file, CstRuleStore, RuleLevel};
+    use structopt::StructOpt;
+
+    const SOURCE: &str = "if (a) {}\n";
+
+    /// Report the result of linting a file whose rule is run at a level with some arguments.
+    fn exit_code(level: RuleLevel, args: &[&str]) -> ExitCode {
+        let file = JsFile::new_virtual(SOURCE.to_string(), "foo.js".to_string());
+        let mut walker = FileWalker::empty();
+        let id = file.id;
+        walker.files.insert(id, file);
+
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![Box::new(NoEmpty::default()) as Box<_>]);
+        store.set_level("no-empty", level);
+        let result = lint_file(id, SOURCE, false, &store, false).unwrap();
+
+        let options = Options::from_iter(
+            ["rslint", "--format", "json"]
+                .iter()
+                .chain(args)
+                .collect::<Vec<_>>(),
+        );
+        report(&mut [result], &walker, &options, false)
+    }
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(exit_code(RuleLevel::Off, &[]), ExitCode::Success);
+        assert_eq!(exit_code(RuleLevel::Info, &[]), ExitCode::Success);
+        assert_eq!(exit_code(RuleLevel::Error, &[]), ExitCode::LintErrors);
+        assert_eq!(
+            report(
+                &mut [],
+                &FileWalker::empty(),
+                &Options::from_iter(&["rslint"]),
+                true
+            ),
+            ExitCode::LintErrors,
+            "files which could not be linted fail the run"
+        );
+
+        let root = std::env::temp_dir().join("rslint-exit-codes");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join(CONFIG_NAME), "[rules.errors]\nno-emtpy = {}").unwrap();
+        let path = root.join("foo.js");
+        let options = Options::from_iter(&["rslint", "--print-config", path.to_str().unwrap()]);
+        assert_eq!(run(options), ExitCode::UsageError);
+    }
+
+    #[test]
+    fn max_warnings() {
+        assert_eq!(exit_code(RuleLevel::Warning, &[]), ExitCode::Success);
+        assert_eq!(
+            exit_code(RuleLevel::Warning, &["--max-warnings", "1"]),
+            ExitCode::Success
+        );
+        assert_eq!(
+            exit_code(RuleLevel::Warning, &["--max-warnings", "0"]),
+            ExitCode::LintErrors
+        );
+        // notes are not warnings
+        assert_eq!(
+            exit_code(RuleLevel::Info, &["--max-warnings", "0"]),
+            ExitCode::Success
+        );
+    }
+}
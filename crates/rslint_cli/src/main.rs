@@ -1,37 +1,56 @@
-use rslint_cli::ExplanationRunner;
+use rslint_cli::{ExitCode, ExplanationRunner, Options, SubCommand};
+use structopt::clap::ErrorKind;
 use structopt::StructOpt;
 
-#[derive(Debug, StructOpt)]
-#[structopt(
-    name = "rslint",
-    about = "An extremely fast and configurable JavaScript linter"
-)]
-pub(crate) struct Options {
-    /// Whether to include potentially spammy details in rule diagnostics.
-    #[structopt(short, long)]
-    verbose: bool,
-    /// A glob pattern to lint.
-    #[structopt(default_value = "./")]
-    files: String,
-    #[structopt(subcommand)]
-    cmd: Option<SubCommand>,
-}
-
-#[derive(Debug, StructOpt)]
-pub(crate) enum SubCommand {
-    /// Explain a list of rules, ex: `explain getter-return, no-cond-assign`
-    Explain { rules: Vec<String> },
-}
-
 fn main() {
     #[cfg(not(debug_assertions))]
     std::panic::set_hook(Box::new(rslint_cli::panic_hook));
+    // the default hook prints a backtrace, which is more useful than a bug report message while developing
+    #[cfg(debug_assertions)]
+    {
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            default_hook(info);
+            ExitCode::Panic.exit()
+        }));
+    }
 
-    let opt = Options::from_args();
+    let opt = match Options::from_iter_safe(std::env::args_os()) {
+        Ok(opt) => opt,
+        Err(err)
+            if matches!(
+                err.kind,
+                ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed
+            ) =>
+        {
+            err.exit()
+        }
+        Err(err) => {
+            eprintln!("{}", err.message);
+            ExitCode::UsageError.exit()
+        }
+    };
 
-    if let Some(SubCommand::Explain { rules }) = opt.cmd {
-        ExplanationRunner::new(rules).print();
-    } else {
-        rslint_cli::run(opt.files, opt.verbose);
-    }
+    let code = match opt.cmd {
+        Some(SubCommand::Explain { rules }) => {
+            let count = rules.len();
+            let runner = ExplanationRunner::new(rules);
+            let invalid = runner.rules.len() != count;
+            runner.print();
+            if invalid {
+                ExitCode::UsageError
+            } else {
+                ExitCode::Success
+            }
+        }
+        Some(SubCommand::Init {
+            env,
+            modules,
+            force,
+        }) => rslint_cli::init(env, modules, force),
+        Some(SubCommand::Rules { json }) => rslint_cli::print_rules(json),
+        Some(SubCommand::Schema) => rslint_cli::print_schema(),
+        None => rslint_cli::run(opt),
+    };
+    code.exit()
 }
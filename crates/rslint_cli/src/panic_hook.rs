@@ -31,5 +31,5 @@ pub fn panic_hook(info: &PanicInfo) {
 
     write(format!("message: {}", msg).as_str());
     write(format!("location: {}", location).as_str());
-    std::process::exit(-1);
+    crate::ExitCode::Panic.exit();
 }
@@ -0,0 +1,180 @@
+//! Printing the config a file is linted with, with `--print-config`.
+
+use crate::{
+    config::Config, emit_config_error, inline_toml, lint_err, rule_config, toml_key,
+    ConfigResolver, ExitCode, OutputFormat,
+};
+use rslint_core::{
+    globals::{GlobalKind, DEFAULT_ENVIRONMENTS},
+    CstRuleStore, RuleLevel,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The config a file is linted with, after merging every config and override which applies to the file.
+///
+/// Every rule which is run is listed in `errors` or `warnings` with its config, rules which are run as info
+/// are listed in `errors` and given their level in `levels`, and every enabled environment
+/// and disabled default environment is listed, so the resolved config is a valid config which runs the same rules.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolvedConfig {
+    pub rules: ResolvedRules,
+    pub env: BTreeMap<String, bool>,
+    pub globals: BTreeMap<String, GlobalKind>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResolvedRules {
+    pub errors: BTreeMap<&'static str, serde_json::Value>,
+    pub warnings: BTreeMap<&'static str, serde_json::Value>,
+    pub levels: BTreeMap<&'static str, RuleLevel>,
+}
+
+impl ResolvedConfig {
+    /// Resolve the rules and environments of a merged config, or of the default config if no config applies.
+    pub fn new(config: Option<&Config>) -> Self {
+        let store = config.map_or_else(|| CstRuleStore::new().builtins(), Config::store);
+        let mut rules = ResolvedRules::default();
+        for rule in store.rules.iter() {
+            let level = config.map_or(RuleLevel::Error, |config| config.rule_level(rule.name()));
+            let rules = match level {
+                RuleLevel::Warning => &mut rules.warnings,
+                RuleLevel::Info | RuleLevel::Off => {
+                    rules.levels.insert(rule.name(), level);
+                    &mut rules.errors
+                }
+                RuleLevel::Error => &mut rules.errors,
+            };
+            rules.insert(rule.name(), rule_config(&**rule));
+        }
+
+        let mut env = DEFAULT_ENVIRONMENTS
+            .iter()
+            .map(|env| (env.to_string(), true))
+            .collect::<BTreeMap<_, _>>();
+        if let Some(config) = config {
+            env.extend(config.env.clone());
+        }
+        // disabled default environments are kept, they would be enabled otherwise
+        env.retain(|env, enabled| *enabled || DEFAULT_ENVIRONMENTS.contains(&env.as_str()));
+
+        Self {
+            rules,
+            env,
+            globals: config
+                .map(|config| config.globals.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl ResolvedConfig {
+    /// Write the config as TOML, with the config of each rule on a single line.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        let mut toml = String::new();
+        let tables = vec![
+            ("rules.errors", toml::Value::try_from(&self.rules.errors)?),
+            (
+                "rules.warnings",
+                toml::Value::try_from(&self.rules.warnings)?,
+            ),
+            ("rules.levels", toml::Value::try_from(&self.rules.levels)?),
+            ("env", toml::Value::try_from(&self.env)?),
+            ("globals", toml::Value::try_from(&self.globals)?),
+        ];
+        for (name, table) in tables {
+            toml.push_str(&format!("[{}]\n", name));
+            for (key, value) in table.as_table().into_iter().flatten() {
+                toml.push_str(&format!("{} = {}\n", toml_key(key), inline_toml(value)));
+            }
+            toml.push('\n');
+        }
+        Ok(toml)
+    }
+}
+
+/// Print the config a file is linted with, as JSON with `--format json` and as TOML otherwise.
+pub fn print_config(path: &Path, format: OutputFormat) -> ExitCode {
+    let config = match ConfigResolver::new().resolve(path) {
+        Ok(config) => config,
+        Err(err) => {
+            emit_config_error(&err);
+            return ExitCode::UsageError;
+        }
+    };
+    let resolved = ResolvedConfig::new(config.as_ref());
+
+    let printed = if format == OutputFormat::Json {
+        serde_json::to_string_pretty(&resolved).map_err(|err| err.to_string())
+    } else {
+        resolved.to_toml().map_err(|err| err.to_string())
+    };
+    match printed {
+        Ok(printed) => {
+            println!("{}", printed.trim_end());
+            ExitCode::Success
+        }
+        Err(err) => {
+            lint_err!(
+                "failed to print the config of `{}`: {}",
+                path.display(),
+                err
+            );
+            ExitCode::UsageError
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_rules_and_environments() {
+        let config = toml::from_str::<Config>(
+            "
+            [rules]
+            groups = [\"errors\"]
+            allowed = [\"no-debugger\"]
+            [rules.warnings]
+            no-empty = { allowEmptyCatch = true }
+            [rules.levels]
+            no-extra-semi = \"info\"
+            no-sparse-arrays = \"off\"
+            [env]
+            es2021 = false
+            node = true
+            ",
+        )
+        .unwrap();
+
+        let resolved = ResolvedConfig::new(Some(&config));
+        assert!(resolved.rules.errors.contains_key("no-extra-semi"));
+        assert!(!resolved.rules.errors.contains_key("no-debugger"));
+        assert!(!resolved.rules.errors.contains_key("no-sparse-arrays"));
+        assert_eq!(
+            resolved.rules.levels.iter().collect::<Vec<_>>(),
+            vec![(&"no-extra-semi", &RuleLevel::Info)]
+        );
+        assert_eq!(
+            resolved.rules.warnings["no-empty"]["allowEmptyCatch"],
+            serde_json::Value::Bool(true)
+        );
+        assert_eq!(
+            resolved.env.iter().collect::<Vec<_>>(),
+            vec![
+                (&"es2021".to_string(), &false),
+                (&"node".to_string(), &true)
+            ]
+        );
+
+        // the resolved config is a config which runs the same rules
+        let reparsed = toml::from_str::<Config>(&resolved.to_toml().unwrap()).unwrap();
+        let resolved_again = ResolvedConfig::new(Some(&reparsed));
+        assert_eq!(resolved.rules.errors, resolved_again.rules.errors);
+        assert_eq!(resolved.rules.warnings, resolved_again.rules.warnings);
+        assert_eq!(resolved.rules.levels, resolved_again.rules.levels);
+        assert_eq!(resolved.env, resolved_again.env);
+    }
+}
@@ -0,0 +1,119 @@
+//! Listing the builtin rules with `rslint rules`.
+
+use crate::{emit_config_error, inline_toml, rule_config, ConfigResolver, ExitCode};
+use rslint_core::{CstRuleStore, RuleLevel};
+use serde::Serialize;
+use std::env::current_dir;
+
+/// A builtin rule and how it is configured by the config of the working directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleInfo {
+    pub name: &'static str,
+    pub group: &'static str,
+    pub description: &'static str,
+    /// Whether the rule is enabled by the `rslint:recommended` preset.
+    pub recommended: bool,
+    /// Whether `--fix` can fix problems reported by the rule.
+    pub fixable: bool,
+    /// Why the rule is deprecated, if it is deprecated.
+    pub deprecated: Option<&'static str>,
+    /// Whether the rule is run.
+    pub enabled: bool,
+    /// The level of the rule, `off` if it is not run.
+    pub level: RuleLevel,
+    /// The config of the rule, which is the default config if the rule is not run.
+    pub config: serde_json::Value,
+}
+
+/// Print every builtin rule and how it is configured by the config of the working directory,
+/// as a table or as JSON.
+pub fn print_rules(json: bool) -> ExitCode {
+    let rules = match rule_infos() {
+        Some(rules) => rules,
+        None => return ExitCode::UsageError,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rules).unwrap());
+        return ExitCode::Success;
+    }
+
+    let width = rules.iter().map(|rule| rule.name.len()).max().unwrap_or(0);
+    let mut group = "";
+    for rule in &rules {
+        if rule.group != group {
+            group = rule.group;
+            println!("{}", group);
+        }
+        let mut description = rule.description.to_string();
+        if rule.fixable {
+            description.push_str(" (fixable)");
+        }
+        if let Some(reason) = rule.deprecated {
+            description.push_str(&format!(" (deprecated: {})", reason));
+        }
+        println!(
+            "  {:width$}  {:7}  {}",
+            rule.name,
+            rule.level.as_str(),
+            description,
+            width = width
+        );
+        let config = toml::Value::try_from(&rule.config).ok();
+        if let Some(config) =
+            config.filter(|config| config.as_table().map_or(false, |table| !table.is_empty()))
+        {
+            println!(
+                "  {:width$}  {:7}  {}",
+                "",
+                "",
+                inline_toml(&config),
+                width = width
+            );
+        }
+    }
+    ExitCode::Success
+}
+
+/// The builtin rules sorted by group and name, with their config from the config of the working directory.
+/// Config errors are emitted right away.
+pub fn rule_infos() -> Option<Vec<RuleInfo>> {
+    let config = match ConfigResolver::new().dir_config(current_dir().unwrap_or_default()) {
+        Ok(config) => config,
+        Err(err) => {
+            emit_config_error(&err);
+            return None;
+        }
+    };
+    let store = config
+        .as_ref()
+        .map_or_else(|| CstRuleStore::new().builtins(), |config| config.store());
+
+    let mut rules = CstRuleStore::new()
+        .builtins()
+        .rules
+        .into_iter()
+        .map(|builtin| {
+            let configured = store.get(builtin.name());
+            let level = if configured.is_some() {
+                store.level(builtin.name())
+            } else {
+                RuleLevel::Off
+            };
+            let meta = builtin.meta();
+            RuleInfo {
+                name: meta.name,
+                group: meta.group,
+                description: meta.description,
+                recommended: meta.recommended,
+                fixable: meta.fixable,
+                deprecated: meta.deprecated,
+                enabled: configured.is_some(),
+                level,
+                config: rule_config(&*configured.unwrap_or(builtin)),
+            }
+        })
+        .collect::<Vec<_>>();
+    rules.sort_by_key(|rule| (rule.group, rule.name));
+    Some(rules)
+}
@@ -0,0 +1,324 @@
+//! A JSON Schema of the config, printed with `rslint schema`, which editors with TOML schema support
+//! use to complete and validate configs.
+
+use crate::{rule_config, ExitCode, PRESET_NAMES, PRESET_PREFIX};
+use heck::MixedCase;
+use rslint_core::{
+    globals::ENVIRONMENTS, ConfigField, CstRule, CstRuleStore, RuleLevel, RuleMeta, GROUP_NAMES,
+};
+use serde_json::{json, Map, Value};
+
+/// The JSON Schema of `rslintrc.toml`, including the config fields of every builtin rule with their defaults.
+pub fn config_schema() -> Value {
+    let mut rules = CstRuleStore::new().builtins().rules;
+    rules.sort_by_key(|rule| rule.name());
+    let rule_names = rules.iter().map(|rule| rule.name()).collect::<Vec<_>>();
+    let rule_configs = rules
+        .iter()
+        .map(|rule| (rule.name().to_string(), rule_schema(&**rule)))
+        .collect::<Map<_, _>>();
+    let rule_levels = rules
+        .iter()
+        .map(|rule| {
+            let schema =
+                json!({ "description": rule.meta().description, "enum": RuleLevel::NAMES });
+            (rule.name().to_string(), schema)
+        })
+        .collect::<Map<_, _>>();
+    let presets = PRESET_NAMES
+        .iter()
+        .map(|preset| format!("{}{}", PRESET_PREFIX, preset))
+        .collect::<Vec<_>>();
+    let environments = ENVIRONMENTS
+        .iter()
+        .map(|env| (env.to_string(), json!({ "type": "boolean" })))
+        .collect::<Map<_, _>>();
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "rslintrc.toml",
+        "description": "The config of RSLint.",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "root": {
+                "description": "Whether to stop looking for configs in parent directories.",
+                "type": "boolean",
+                "default": false
+            },
+            "extends": {
+                "description": "Configs to extend, either paths relative to this config or builtin presets such as `rslint:recommended`.",
+                "type": "array",
+                "items": {
+                    "anyOf": [
+                        { "enum": presets },
+                        { "type": "string" }
+                    ]
+                },
+                "default": []
+            },
+            "rules": { "$ref": "#/definitions/rules" },
+            "env": { "$ref": "#/definitions/env" },
+            "globals": { "$ref": "#/definitions/globals" },
+            "ignore": {
+                "description": "Patterns of files to ignore in gitignore syntax, relative to the config.",
+                "type": "array",
+                "items": { "type": "string" },
+                "default": []
+            },
+            "gitignore": {
+                "description": "Whether to also ignore the files ignored by the `.gitignore` of the linting directory.",
+                "type": "boolean",
+                "default": false
+            },
+            "extensions": {
+                "description": "The extensions of files to lint.",
+                "type": "array",
+                "items": { "type": "string" },
+                "default": ["js", "mjs"]
+            },
+            "modules": {
+                "description": "The module system of the linted files, with `esm` every file but `.cjs` files is parsed as a module.",
+                "enum": ["esm", "commonjs"],
+                "default": "commonjs"
+            },
+            "overrides": {
+                "description": "Configuration which only applies to some files.",
+                "type": "array",
+                "items": { "$ref": "#/definitions/override" },
+                "default": []
+            }
+        },
+        "definitions": {
+            "override": {
+                "description": "Configuration which only applies to the files matching some patterns.",
+                "type": "object",
+                "additionalProperties": false,
+                "required": ["files"],
+                "properties": {
+                    "files": {
+                        "description": "The patterns of files this override applies to, in gitignore syntax relative to the config.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "rules": { "$ref": "#/definitions/rules" },
+                    "env": { "$ref": "#/definitions/env" },
+                    "globals": { "$ref": "#/definitions/globals" }
+                }
+            },
+            "rules": {
+                "description": "The rules to run and their config.",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "errors": {
+                        "description": "Rules to run as errors with their config.",
+                        "$ref": "#/definitions/ruleConfigs"
+                    },
+                    "warnings": {
+                        "description": "Rules to run as warnings with their config.",
+                        "$ref": "#/definitions/ruleConfigs"
+                    },
+                    "groups": {
+                        "description": "Groups of rules to run as errors.",
+                        "type": "array",
+                        "items": { "enum": GROUP_NAMES },
+                        "default": []
+                    },
+                    "allowed": {
+                        "description": "Rules which are never run.",
+                        "type": "array",
+                        "items": { "enum": rule_names },
+                        "default": []
+                    },
+                    "levels": {
+                        "description": "The level of rules, which takes precedence over the table they are configured in. Rules which are only given a level are run with their default config.",
+                        "$ref": "#/definitions/ruleLevels"
+                    }
+                }
+            },
+            "ruleConfigs": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": rule_configs,
+                "patternProperties": camel_case_aliases(&rule_names, "ruleConfigs")
+            },
+            "ruleLevels": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": rule_levels,
+                "patternProperties": camel_case_aliases(&rule_names, "ruleLevels")
+            },
+            "env": {
+                "description": "Environments to enable or disable, `es2021` is enabled by default.",
+                "type": "object",
+                "additionalProperties": { "type": "boolean" },
+                "properties": environments
+            },
+            "globals": {
+                "description": "Extra globals and how they may be used.",
+                "type": "object",
+                "additionalProperties": {
+                    "enum": ["readonly", "writable", "off"]
+                }
+            }
+        }
+    })
+}
+
+/// References to the schemas of rules in a definition by their camelCase names, which configs also accept.
+/// They are pattern properties so editors only complete the kebab-case names.
+fn camel_case_aliases(rule_names: &[&str], definition: &str) -> Map<String, Value> {
+    rule_names
+        .iter()
+        .filter(|name| name.contains('-'))
+        .map(|name| {
+            let reference = format!("#/definitions/{}/properties/{}", definition, name);
+            (
+                format!("^{}$", name.to_mixed_case()),
+                json!({ "$ref": reference }),
+            )
+        })
+        .collect()
+}
+
+/// The schema of the config of a rule, with the default of each field.
+fn rule_schema(rule: &dyn CstRule) -> Value {
+    let meta = rule.meta();
+    let defaults = rule_config(rule);
+    let properties = meta
+        .config
+        .iter()
+        .map(|field| {
+            let mut schema = field_schema(field);
+            if let Some(default) = defaults.get(&field.name) {
+                schema["default"] = default.clone();
+            }
+            (field.name.clone(), schema)
+        })
+        .collect::<Map<_, _>>();
+
+    json!({
+        "description": rule_description(&meta),
+        "type": "object",
+        "additionalProperties": false,
+        "properties": properties
+    })
+}
+
+fn rule_description(meta: &RuleMeta) -> String {
+    match (meta.deprecated, meta.replaced_by) {
+        (Some(reason), Some(rule)) => format!(
+            "{}\n\nDeprecated: {}, use `{}` instead.",
+            meta.description, reason, rule
+        ),
+        (Some(reason), None) => format!("{}\n\nDeprecated: {}", meta.description, reason),
+        _ => meta.description.to_string(),
+    }
+}
+
+/// The schema of a config field from its Rust type, or from the names it accepts if it is an enum.
+pub(crate) fn field_schema(field: &ConfigField) -> Value {
+    let mut schema = if field.values.is_empty() {
+        type_schema(field.ty)
+    } else {
+        json!({ "type": "string", "enum": field.values })
+    };
+    if !field.docs.is_empty() {
+        schema["description"] = Value::String(field.docs.clone());
+    }
+    schema
+}
+
+/// The schema of a value of a Rust type, which accepts any value if the type is not known.
+pub(crate) fn type_schema(ty: &str) -> Value {
+    let ty = ty.replace(' ', "");
+    if let Some(inner) = ty
+        .strip_prefix("Option<")
+        .and_then(|ty| ty.strip_suffix('>'))
+    {
+        return type_schema(inner);
+    }
+    if let Some(inner) = ty.strip_prefix("Vec<").and_then(|ty| ty.strip_suffix('>')) {
+        return json!({ "type": "array", "items": type_schema(inner) });
+    }
+    match ty.rsplit("::").next().unwrap_or_default() {
+        "bool" => json!({ "type": "boolean" }),
+        "String" | "&str" | "char" => json!({ "type": "string" }),
+        "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => {
+            json!({ "type": "integer" })
+        }
+        "f32" | "f64" => json!({ "type": "number" }),
+        "Pattern" => json!({ "type": "string", "format": "regex" }),
+        _ => json!({}),
+    }
+}
+
+/// Print the JSON Schema of the config.
+pub fn print_schema() -> ExitCode {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&config_schema()).unwrap()
+    );
+    ExitCode::Success
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_rule_configs() {
+        let schema = config_schema();
+        let rules = &schema["definitions"]["ruleConfigs"]["properties"];
+        assert_eq!(
+            rules.as_object().unwrap().len(),
+            CstRuleStore::new().builtins().rules.len()
+        );
+
+        let no_empty = &rules["no-empty"]["properties"]["allowEmptyCatch"];
+        assert_eq!(no_empty["type"], "boolean");
+        assert_eq!(no_empty["default"], false);
+        assert!(no_empty["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("Whether to allow empty `catch` clauses"));
+
+        let disallowed = &rules["no-inner-declarations"]["properties"]["disallowed"];
+        assert_eq!(disallowed["items"]["type"], "string");
+        assert_eq!(disallowed["default"], json!(["functions"]));
+
+        // optional fields without a default are not given one
+        let pattern = &rules["no-unused-vars"]["properties"]["ignorePattern"];
+        assert_eq!(pattern["type"], "string");
+        assert!(pattern.get("default").is_none());
+
+        let levels = &schema["definitions"]["ruleLevels"]["properties"];
+        assert_eq!(
+            levels["no-empty"]["enum"],
+            json!(["off", "info", "warn", "error"])
+        );
+    }
+
+    #[test]
+    fn accepts_camel_case_rule_names() {
+        use heck::KebabCase;
+
+        let schema = config_schema();
+        for definition in &["ruleConfigs", "ruleLevels"] {
+            let aliases = schema["definitions"][definition]["patternProperties"]
+                .as_object()
+                .unwrap();
+            assert_eq!(
+                aliases["^noEmpty$"]["$ref"],
+                format!("#/definitions/{}/properties/no-empty", definition)
+            );
+            // configs find rules by the kebab-case form of their name
+            for (pattern, alias) in aliases {
+                let name = pattern.trim_start_matches('^').trim_end_matches('$');
+                let reference = alias["$ref"].as_str().unwrap();
+                assert!(reference.ends_with(&format!("/{}", name.to_kebab_case())));
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! The state of a lint run, which is kept alive between runs in watch mode.
+
+use crate::{
+    baseline::Baseline,
+    cache,
+    config::{self, ConfigError, ConfigKey, ConfigResolver},
+    emit_config_error, emit_diagnostic, lint_err, lint_note, lint_warn, ChangedFiles, DirFilters,
+    ExitCode, FileFilter, FileWalker, JsFile, JsFileKind, Options, STDIN_NAME,
+};
+use hashbrown::HashMap;
+use rayon::prelude::*;
+use rslint_core::{fix_file, lint_file, CstRuleStore, LintResult};
+use std::env::current_dir;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The files to lint, and the configs and rule stores they are linted with.
+#[derive(Debug)]
+pub(crate) struct Session {
+    pub cwd: PathBuf,
+    pub resolver: ConfigResolver,
+    pub filter: FileFilter,
+    pub walker: FileWalker,
+    pub rules: RuleStores,
+    /// The files changed according to git, if only changed files are linted.
+    pub changed: Option<ChangedFiles>,
+    /// The baseline of problems which are not reported.
+    pub baseline: Option<Baseline>,
+}
+
+/// The config and rule store of every file, files with the same configs and overrides share a store.
+#[derive(Debug, Default)]
+pub(crate) struct RuleStores {
+    keys: HashMap<ConfigKey, usize>,
+    configs: Vec<Option<config::Config>>,
+    stores: Vec<CstRuleStore>,
+    /// The hash of each config, see [`cache::config_hash`].
+    hashes: Vec<u64>,
+    file_configs: HashMap<usize, usize>,
+}
+
+/// The files which were linted, the files which were fixed with the fixed source and the number of fixes,
+/// and whether any file could not be linted.
+pub(crate) type LintOutput<'s> = (Vec<LintResult<'s>>, Vec<(usize, String, usize)>, bool);
+
+impl Session {
+    /// Load the configs and the files matched by the options.
+    /// Errors are emitted right away and the code the process should exit with is returned.
+    pub fn load(options: &Options) -> Result<Self, ExitCode> {
+        let paths = if options.stdin {
+            None
+        } else {
+            match glob::glob(&options.files) {
+                Ok(paths) => Some(paths),
+                Err(err) => {
+                    lint_err!("Invalid glob pattern: {}", err);
+                    return Err(ExitCode::UsageError);
+                }
+            }
+        };
+
+        let cwd = current_dir().unwrap_or_default();
+        let mut resolver = ConfigResolver::new();
+        let cwd_configs = resolver.chain(&cwd).map_err(config_error)?;
+
+        let ignore_path = options.ignore_path.as_deref();
+        let filter = match FileFilter::from_configs(&cwd_configs, options.no_ignore, ignore_path) {
+            Ok(filter) => filter,
+            Err(err) => {
+                lint_err!(
+                    "failed to read the ignore file `{}`: {}",
+                    ignore_path.unwrap_or_else(|| Path::new("")).display(),
+                    err
+                );
+                return Err(ExitCode::UsageError);
+            }
+        };
+        let revision = options.git_revision();
+        if options.changed_lines && revision.is_none() {
+            lint_err!("`--changed-lines` requires `--changed-since` or `--staged`");
+            return Err(ExitCode::UsageError);
+        }
+        let changed = match revision.as_ref().map(ChangedFiles::load).transpose() {
+            Ok(changed) => changed,
+            Err(err) => {
+                lint_err!("failed to find the changed files with git: {}", err);
+                return Err(ExitCode::UsageError);
+            }
+        };
+
+        let mut filters = DirFilters::new(&filter, &mut resolver);
+        let walker = match paths {
+            Some(paths) => FileWalker::from_glob(paths, &mut filters, changed.as_ref()),
+            None => {
+                let name = options.stdin_filename.as_ref();
+                if let Some(path) = name.filter(|path| filters.is_ignored(path, false)) {
+                    lint_warn!(
+                        "`{}` is ignored, use `--no-ignore` to lint it anyway",
+                        path.display()
+                    );
+                    return Err(ExitCode::Success);
+                }
+                match read_stdin(name.map(|name| name.as_path())) {
+                    Ok(mut walker) => {
+                        if let Some(changed) = changed.as_ref() {
+                            walker.files.retain(|_, file| {
+                                file.path
+                                    .as_ref()
+                                    .map_or(false, |path| changed.contains(path))
+                            });
+                        }
+                        walker
+                    }
+                    Err(err) => {
+                        lint_err!("failed to read from stdin: {}", err);
+                        return Err(ExitCode::UsageError);
+                    }
+                }
+            }
+        };
+
+        if changed.is_some() && walker.files.is_empty() {
+            lint_note!("No changed files to lint");
+            return Err(ExitCode::Success);
+        }
+
+        if walker.files.is_empty() {
+            lint_err!("No matching files found");
+            return Err(ExitCode::UsageError);
+        }
+
+        let baseline = match options.baseline.as_ref().map(Baseline::load).transpose() {
+            Ok(baseline) => baseline,
+            Err(err) => {
+                lint_err!(
+                    "failed to read the baseline `{}`: {}",
+                    options.baseline.as_ref().unwrap().display(),
+                    err
+                );
+                return Err(ExitCode::UsageError);
+            }
+        };
+
+        let mut session = Self {
+            cwd,
+            resolver,
+            filter,
+            walker,
+            rules: RuleStores::default(),
+            changed,
+            baseline,
+        };
+        for id in session.ids() {
+            session.add_config(id).map_err(config_error)?;
+        }
+        Ok(session)
+    }
+
+    /// The ids of every file of the walker.
+    pub fn ids(&self) -> Vec<usize> {
+        self.walker.files.keys().copied().collect()
+    }
+
+    /// Find the config of a file and make its rule store if no other file has the same config.
+    pub fn add_config(&mut self, id: usize) -> Result<(), ConfigError> {
+        let file = &self.walker.files[&id];
+        // virtual files use the config of their name, which is a path relative to the working directory
+        let path = file
+            .path
+            .clone()
+            .unwrap_or_else(|| self.cwd.join(&file.name));
+        let key = self.resolver.key(&path)?;
+
+        let rules = &mut self.rules;
+        let idx = match rules.keys.get(&key) {
+            Some(idx) => *idx,
+            None => {
+                let config = self.resolver.config(&key);
+                rules.stores.push(
+                    config
+                        .as_ref()
+                        .map_or_else(|| CstRuleStore::new().builtins(), config::Config::store),
+                );
+                rules.hashes.push(cache::config_hash(config.as_ref()));
+                rules.configs.push(config);
+                rules.keys.insert(key, rules.configs.len() - 1);
+                rules.configs.len() - 1
+            }
+        };
+        rules.file_configs.insert(id, idx);
+
+        let modules = rules.configs[idx]
+            .as_ref()
+            .and_then(|config| config.modules);
+        self.walker.files.get_mut(&id).unwrap().kind = JsFileKind::from_config(&path, modules);
+        Ok(())
+    }
+
+    /// Remove the diagnostics which are not on changed lines if `--changed-lines` is used,
+    /// and the diagnostics which are in the baseline if `--baseline` is used.
+    pub fn filter_results(&self, results: &mut [LintResult], options: &Options) {
+        if let Some(changed) = self.changed.as_ref().filter(|_| options.changed_lines) {
+            changed.restrict_to_changed_lines(results, &self.walker);
+        }
+        if let Some(baseline) = self.baseline.as_ref() {
+            baseline.suppress(results, &self.walker);
+        }
+    }
+
+    /// Remove a file, returning whether it was loaded.
+    pub fn remove_file(&mut self, id: usize) -> bool {
+        self.rules.file_configs.remove(&id);
+        self.walker.files.remove(&id).is_some()
+    }
+}
+
+impl RuleStores {
+    /// Lint some files of the walker with the rule store of their config, fixing them if `fix` is true.
+    ///
+    /// Files which could not be linted (e.g. because of invalid directives) are reported right away.
+    pub fn lint(
+        &self,
+        walker: &FileWalker,
+        ids: &[usize],
+        fix: bool,
+        verbose: bool,
+    ) -> LintOutput<'_> {
+        let unlintable = AtomicBool::new(false);
+        let mut fixed_files = vec![];
+        let results = ids
+            .par_iter()
+            .map(|id| {
+                let file = &walker.files[id];
+                let store = &self.stores[self.file_configs[id]];
+                let module = file.kind == JsFileKind::Module;
+                if fix {
+                    fix_file(*id, &file.source, module, store, verbose)
+                        .map(|res| (Some((*id, res.fixed, res.fix_count)), res.result))
+                } else {
+                    lint_file(*id, &file.source, module, store, verbose).map(|res| (None, res))
+                }
+            })
+            .filter_map(|res| {
+                if let Err(diagnostic) = res {
+                    emit_diagnostic(diagnostic, walker);
+                    unlintable.store(true, Ordering::Relaxed);
+                    None
+                } else {
+                    res.ok()
+                }
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|(fixed, res)| {
+                fixed_files.extend(fixed.filter(|(_, _, count)| *count > 0));
+                res
+            })
+            .collect();
+
+        (results, fixed_files, unlintable.into_inner())
+    }
+
+    /// The hash of a file and its config, which is used to find its cached result.
+    pub fn hash(&self, file: &JsFile, verbose: bool) -> u64 {
+        cache::file_hash(
+            &file.source,
+            self.hashes[self.file_configs[&file.id]],
+            verbose,
+        )
+    }
+
+    /// The rule store a file is linted with.
+    pub fn store(&self, id: usize) -> &CstRuleStore {
+        &self.stores[self.file_configs[&id]]
+    }
+}
+
+fn config_error(err: ConfigError) -> ExitCode {
+    emit_config_error(&err);
+    ExitCode::UsageError
+}
+
+/// Make a walker with a single virtual file read from stdin, named after `--stdin-filename` if given.
+fn read_stdin(name: Option<&Path>) -> io::Result<FileWalker> {
+    let mut source = String::new();
+    io::stdin().read_to_string(&mut source)?;
+
+    let name = name.map_or_else(
+        || STDIN_NAME.to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    let file = JsFile::new_virtual(source, name);
+    let mut walker = FileWalker::empty();
+    walker.files.insert(file.id, file);
+    Ok(walker)
+}
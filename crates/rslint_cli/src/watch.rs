@@ -0,0 +1,355 @@
+//! Watch mode, which lints files again when they change.
+
+use crate::{
+    apply_fixed_files, emit_config_error, ignore::normalize, lint_err, lint_note, report,
+    session::Session, DirFilters, ExitCode, JsFile, Options, OutputFormat, CONFIG_NAME,
+    IGNORE_FILE_NAME,
+};
+use hashbrown::{HashMap, HashSet};
+use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use rslint_core::{CstRuleStore, Diagnostic, LintResult, RuleResult};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+use walkdir::WalkDir;
+
+/// How long to wait for more changes before linting, editors often write files in multiple steps.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The parts of a lint result which do not borrow its rule store, so results can be kept
+/// while the stores are rebuilt.
+#[derive(Debug)]
+struct Retained {
+    parser_diagnostics: Vec<Diagnostic>,
+    rule_results: std::collections::HashMap<&'static str, RuleResult>,
+    directive_diagnostics: Vec<Diagnostic>,
+}
+
+impl Retained {
+    fn new(result: LintResult) -> (usize, Self) {
+        let retained = Self {
+            parser_diagnostics: result.parser_diagnostics,
+            rule_results: result.rule_results,
+            directive_diagnostics: result.directive_diagnostics,
+        };
+        (result.file_id, retained)
+    }
+
+    fn into_result(self, file_id: usize, store: &CstRuleStore) -> LintResult<'_> {
+        LintResult {
+            file_id,
+            parser_diagnostics: self.parser_diagnostics,
+            store,
+            rule_results: self.rule_results,
+            directive_diagnostics: self.directive_diagnostics,
+        }
+    }
+}
+
+/// Lint the files matched by the options, then lint changed files again and report the results of every file
+/// until the process is stopped. The session is loaded again if a config or an ignore file changes.
+pub(crate) fn watch(options: &Options) -> ExitCode {
+    let (tx, rx) = channel();
+    let mut watcher = match watcher(tx, DEBOUNCE) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            lint_err!("failed to watch files: {}", err);
+            return ExitCode::UsageError;
+        }
+    };
+    let mut watched = HashSet::new();
+    let mut results = HashMap::new();
+
+    clear_terminal(options);
+    let mut session = match Session::load(options) {
+        Ok(session) => session,
+        Err(code) => return code,
+    };
+    watch_paths(&mut watcher, &mut watched, &mut session, options);
+    let ids = session.ids();
+    lint(&mut session, &ids, &mut results, options);
+    redraw(&session, &mut results, options);
+    let mut session = Some(session);
+
+    while let Some((paths, rescan)) = next_changes(&rx) {
+        let reload = rescan || paths.iter().any(|path| is_config(path, options));
+        match session.as_mut() {
+            Some(current) if !reload => {
+                let ids = update_files(current, &paths, &mut results, options);
+                if ids.is_empty() {
+                    continue;
+                }
+                clear_terminal(options);
+                lint(current, &ids, &mut results, options);
+            }
+            _ => {
+                clear_terminal(options);
+                results.clear();
+                session = Session::load(options).ok();
+                if let Some(new) = session.as_mut() {
+                    watch_paths(&mut watcher, &mut watched, new, options);
+                    let ids = new.ids();
+                    lint(new, &ids, &mut results, options);
+                }
+            }
+        }
+
+        match session.as_ref() {
+            Some(session) => redraw(session, &mut results, options),
+            None => lint_note!("waiting for changes"),
+        }
+    }
+    ExitCode::Success
+}
+
+/// Watch the files and directories matched by the glob, and the configs which apply to the working directory.
+fn watch_paths(
+    watcher: &mut RecommendedWatcher,
+    watched: &mut HashSet<PathBuf>,
+    session: &mut Session,
+    options: &Options,
+) {
+    let entries = glob::glob(&options.files)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok);
+    // configs in the watched directories are already watched, but configs in parent directories are not,
+    // the working directory is watched for new configs
+    let configs = session
+        .resolver
+        .chain(&session.cwd)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|config| config.path.clone());
+    let ignore_file = options
+        .ignore_path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(IGNORE_FILE_NAME));
+
+    let paths = entries
+        .chain(configs)
+        .chain(vec![ignore_file, session.cwd.clone()]);
+
+    for path in paths {
+        let path = normalize(&path);
+        if !path.exists() || !watched.insert(path.clone()) {
+            continue;
+        }
+        let mode = if path.is_dir() && path != session.cwd {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        if let Err(err) = watcher.watch(&path, mode) {
+            lint_err!("failed to watch `{}`: {}", path.display(), err);
+        }
+    }
+}
+
+/// Wait for changes, returning the changed paths and whether changes may have been missed,
+/// or `None` if the watcher stopped.
+fn next_changes(rx: &Receiver<DebouncedEvent>) -> Option<(Vec<PathBuf>, bool)> {
+    let mut events = vec![rx.recv().ok()?];
+    events.extend(rx.try_iter());
+
+    let mut paths = vec![];
+    let mut rescan = false;
+    for event in events {
+        match event {
+            DebouncedEvent::Create(path)
+            | DebouncedEvent::Write(path)
+            | DebouncedEvent::Remove(path) => paths.push(path),
+            DebouncedEvent::Rename(from, to) => paths.extend(vec![from, to]),
+            DebouncedEvent::Rescan => rescan = true,
+            DebouncedEvent::Error(err, path) => match path {
+                Some(path) => lint_err!("failed to watch `{}`: {}", path.display(), err),
+                None => lint_err!("failed to watch files: {}", err),
+            },
+            _ => {}
+        }
+    }
+    Some((paths, rescan))
+}
+
+/// Whether a changed file can change the config or the ignored files, which means the session has to be loaded again.
+fn is_config(path: &Path, options: &Options) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str());
+    matches!(name, Some(CONFIG_NAME) | Some(IGNORE_FILE_NAME) | Some(".gitignore"))
+        // extended configs can have any name
+        || path.extension().map_or(false, |ext| ext == "toml")
+        || options
+            .ignore_path
+            .as_ref()
+            .map_or(false, |ignore_path| normalize(ignore_path) == normalize(path))
+}
+
+/// Update the files of the session with changed paths, returning the ids of the files which have to be linted again.
+fn update_files(
+    session: &mut Session,
+    paths: &[PathBuf],
+    results: &mut HashMap<usize, Retained>,
+    options: &Options,
+) -> Vec<usize> {
+    let entries = glob::glob(&options.files)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| (normalize(&entry), entry))
+        .collect::<Vec<_>>();
+    let mut loaded = session
+        .walker
+        .files
+        .values()
+        .filter_map(|file| Some((normalize(file.path.as_ref()?), file.id)))
+        .collect::<HashMap<_, _>>();
+
+    let mut changed = vec![];
+    for path in paths.iter().map(|path| normalize(path)) {
+        // files in a removed directory do not get their own events
+        let removed = loaded
+            .iter()
+            .filter(|(loaded_path, _)| loaded_path.starts_with(&path) && !loaded_path.exists())
+            .map(|(loaded_path, id)| (loaded_path.clone(), *id))
+            .collect::<Vec<_>>();
+        for (removed_path, id) in removed {
+            loaded.remove(&removed_path);
+            session.remove_file(id);
+            results.remove(&id);
+            changed.retain(|changed_id| *changed_id != id);
+        }
+
+        let mut filters = DirFilters::new(&session.filter, &mut session.resolver);
+        let files = WalkDir::new(&path)
+            .into_iter()
+            .filter_entry(|entry| {
+                let path = entry.path();
+                let filter = filters.get(path.parent().unwrap_or(path));
+                !filter.is_ignored_entry(path, entry.file_type().is_dir())
+            })
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect::<Vec<_>>();
+        let files = files
+            .into_iter()
+            .map(|file| {
+                let linted = filters
+                    .get(file.parent().unwrap_or(&file))
+                    .has_linted_extension(&file)
+                    && !filters.is_ignored(&file, false);
+                (file, linted)
+            })
+            .collect::<Vec<_>>();
+
+        for (file, linted) in files {
+            if let Some(id) = loaded.get(&file) {
+                if let Ok(source) = read_to_string(&file) {
+                    session.walker.files.get_mut(id).unwrap().update_src(source);
+                    changed.push(*id);
+                }
+                continue;
+            }
+
+            let path = match glob_path(&entries, &file) {
+                Some(path) => path,
+                None => continue,
+            };
+            if !linted {
+                continue;
+            }
+            if let Ok(source) = read_to_string(&file) {
+                let js_file = JsFile::new_concrete(source, path);
+                let id = js_file.id;
+                session.walker.files.insert(id, js_file);
+                if let Err(err) = session.add_config(id) {
+                    emit_config_error(&err);
+                    session.remove_file(id);
+                    continue;
+                }
+                loaded.insert(file, id);
+                changed.push(id);
+            }
+        }
+    }
+
+    changed.sort_unstable();
+    changed.dedup();
+    changed
+}
+
+/// The path of a file as the walker of the glob would have found it, which keeps the form of the glob entry
+/// containing it, such as `./src/a.js`, so new files are named like the files found when the session was loaded.
+/// Entries are pairs of a normalized entry and the entry as it was matched by the glob.
+fn glob_path(entries: &[(PathBuf, PathBuf)], file: &Path) -> Option<PathBuf> {
+    entries.iter().find_map(|(normalized, entry)| {
+        let rest = file.strip_prefix(normalized).ok()?;
+        if rest.as_os_str().is_empty() {
+            Some(entry.clone())
+        } else {
+            Some(entry.join(rest))
+        }
+    })
+}
+
+/// Lint files of the session, replacing their previous results.
+fn lint(
+    session: &mut Session,
+    ids: &[usize],
+    results: &mut HashMap<usize, Retained>,
+    options: &Options,
+) {
+    let fix = options.fix || options.fix_dry_run;
+    let (mut lint_results, fixed_files, _) =
+        session
+            .rules
+            .lint(&session.walker, ids, fix, options.verbose);
+    session.filter_results(&mut lint_results, options);
+    results.extend(lint_results.into_iter().map(Retained::new));
+
+    // fixed files are written to disk, which makes them get linted again without any fixes
+    if fix {
+        apply_fixed_files(&mut session.walker, fixed_files, options.fix_dry_run);
+    }
+}
+
+/// Report the results of every file of the session.
+fn redraw(session: &Session, results: &mut HashMap<usize, Retained>, options: &Options) {
+    let mut lint_results = results
+        .drain()
+        .map(|(id, retained)| retained.into_result(id, session.rules.store(id)))
+        .collect::<Vec<_>>();
+    report(&mut lint_results, &session.walker, options, false);
+    results.extend(lint_results.into_iter().map(Retained::new));
+
+    lint_note!("watching for changes, press Ctrl+C to stop");
+}
+
+/// Clear the terminal before linting, the long format is the only format meant to be read in a terminal.
+fn clear_terminal(options: &Options) {
+    if options.format == OutputFormat::Long {
+        eprint!("\x1b[2J\x1b[1;1H");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_paths() {
+        let entries = vec![
+            (PathBuf::from("/project"), PathBuf::from("./")),
+            (PathBuf::from("/other/a.js"), PathBuf::from("../other/a.js")),
+        ];
+        assert_eq!(
+            glob_path(&entries, Path::new("/project/src/b.js")),
+            Some(PathBuf::from("./src/b.js"))
+        );
+        assert_eq!(
+            glob_path(&entries, Path::new("/other/a.js")),
+            Some(PathBuf::from("../other/a.js"))
+        );
+        assert_eq!(glob_path(&entries, Path::new("/elsewhere/c.js")), None);
+    }
+}
@@ -0,0 +1,38 @@
+//! Embeds the markdown docs of every builtin rule generated by `cargo xtask docgen`,
+//! so rule docs are available without network access.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let docs_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("../../docs/rules");
+    println!("cargo:rerun-if-changed={}", docs_dir.display());
+
+    let mut docs = vec![];
+    // the docs are not part of the published crate, in which case rules have no docs
+    for group in fs::read_dir(&docs_dir).into_iter().flatten().flatten() {
+        println!("cargo:rerun-if-changed={}", group.path().display());
+        for file in fs::read_dir(group.path()).into_iter().flatten().flatten() {
+            let path = file.path();
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            if path.extension().map_or(false, |ext| ext == "md") && name != "README" {
+                println!("cargo:rerun-if-changed={}", path.display());
+                docs.push((name, path.canonicalize().unwrap()));
+            }
+        }
+    }
+    docs.sort();
+
+    let mut out = String::from("&[\n");
+    for (name, path) in docs {
+        writeln!(out, "    ({:?}, include_str!({:?})),", name, path).unwrap();
+    }
+    out.push(']');
+    fs::write(
+        Path::new(&env::var("OUT_DIR").unwrap()).join("rule_docs.rs"),
+        out,
+    )
+    .unwrap();
+}
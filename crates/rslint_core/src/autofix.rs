@@ -0,0 +1,205 @@
+//! Automatic fixes offered by rules and the machinery to apply them to a source file.
+//!
+//! Rules attach a [`Fixer`] to a diagnostic through [`DiagnosticBuilder::fix`](crate::DiagnosticBuilder::fix).
+//! Each fixer is a group of text edits which must be applied together. Fixes from every rule are
+//! collected, any fixes which overlap an earlier fix are dropped, then the rest are applied and the file
+//! is linted again, until no more fixes can be applied.
+
+use crate::{lint_file, CstRuleStore, Diagnostic, LintResult, Span};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// The maximum amount of times a file is relinted and fixed, this prevents rules with
+/// conflicting fixes from looping forever.
+pub const MAX_FIX_PASSES: usize = 10;
+
+/// A single text edit, which replaces a range of the source with a string.
+/// Insertions are edits with an empty range and deletions are edits with an empty string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Indel {
+    pub range: Range<usize>,
+    pub insert: String,
+}
+
+/// A group of text edits which make up a single fix for a diagnostic.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Fixer {
+    pub indels: Vec<Indel>,
+}
+
+impl Fixer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the text of a range with a string.
+    pub fn replace(mut self, range: impl Span, text: impl AsRef<str>) -> Self {
+        self.indels.push(Indel {
+            range: range.as_range(),
+            insert: text.as_ref().to_string(),
+        });
+        self
+    }
+
+    /// Insert a string before the start of a range.
+    pub fn insert_before(self, range: impl Span, text: impl AsRef<str>) -> Self {
+        let start = range.as_range().start;
+        self.replace(start..start, text)
+    }
+
+    /// Insert a string after the end of a range.
+    pub fn insert_after(self, range: impl Span, text: impl AsRef<str>) -> Self {
+        let end = range.as_range().end;
+        self.replace(end..end, text)
+    }
+
+    /// Delete the text of a range.
+    pub fn delete(self, range: impl Span) -> Self {
+        self.replace(range, "")
+    }
+
+    /// The range spanning all of the edits of this fix.
+    pub fn range(&self) -> Option<Range<usize>> {
+        let start = self.indels.iter().map(|indel| indel.range.start).min()?;
+        let end = self.indels.iter().map(|indel| indel.range.end).max()?;
+        Some(start..end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indels.is_empty()
+    }
+}
+
+/// Apply as many fixes as possible to a source string.
+///
+/// Fixes are applied in the order of their start, a fix which overlaps a previously accepted fix is skipped,
+/// it will most likely be offered again once the file is relinted.
+/// Returns the fixed source and the amount of fixes applied.
+pub fn apply_fixes<'a>(
+    source: &str,
+    fixes: impl IntoIterator<Item = &'a Fixer>,
+) -> (String, usize) {
+    let mut fixes = fixes
+        .into_iter()
+        .filter_map(|fix| Some((fix.range()?, fix)))
+        .filter(|(range, _)| range.end <= source.len())
+        .collect::<Vec<_>>();
+    fixes.sort_by_key(|(range, _)| (range.start, range.end));
+
+    let mut accepted: Vec<&Indel> = vec![];
+    let mut last_end = None;
+    let mut applied = 0;
+
+    for (range, fix) in fixes {
+        // two insertions at the same offset are also considered overlapping, their order would be ambiguous
+        if last_end.map_or(false, |end| {
+            range.start < end || (range.start == end && range.is_empty())
+        }) {
+            continue;
+        }
+        last_end = Some(range.end);
+        accepted.extend(fix.indels.iter());
+        applied += 1;
+    }
+
+    accepted.sort_by_key(|indel| (indel.range.start, indel.range.end));
+    let mut fixed = source.to_string();
+    for indel in accepted.into_iter().rev() {
+        fixed.replace_range(indel.range.clone(), &indel.insert);
+    }
+    (fixed, applied)
+}
+
+/// The result of linting and fixing a single file.
+#[derive(Debug)]
+pub struct FixResult<'s> {
+    /// The source of the file after all fixes were applied.
+    pub fixed: String,
+    /// The total amount of fixes applied to the file.
+    pub fix_count: usize,
+    /// The result of linting the fixed source.
+    pub result: LintResult<'s>,
+}
+
+/// Lint a file then apply the fixes offered by rules, relinting and fixing until
+/// no more fixes can be applied or until [`MAX_FIX_PASSES`] is reached.
+///
+/// Files with parser errors are never fixed because the edits may interact badly with error recovery.
+pub fn fix_file<'s>(
+    file_id: usize,
+    file_source: impl AsRef<str>,
+    module: bool,
+    store: &'s CstRuleStore,
+    verbose: bool,
+) -> Result<FixResult<'s>, Diagnostic> {
+    let mut fixed = file_source.as_ref().to_string();
+    let mut fix_count = 0;
+    let mut result = lint_file(file_id, &fixed, module, store, verbose)?;
+
+    for _ in 0..MAX_FIX_PASSES {
+        if !result.parser_diagnostics.is_empty() {
+            break;
+        }
+
+        let (new, applied) = apply_fixes(&fixed, result.fixes());
+        if applied == 0 {
+            break;
+        }
+        fix_count += applied;
+        fixed = new;
+        result = lint_file(file_id, &fixed, module, store, verbose)?;
+    }
+
+    Ok(FixResult {
+        fixed,
+        fix_count,
+        result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_fixes_are_skipped() {
+        let fixes = vec![
+            Fixer::new().replace(0usize..3, "bar"),
+            Fixer::new().delete(1usize..2),
+            Fixer::new().insert_after(4usize..5, ";"),
+        ];
+        assert_eq!(
+            apply_fixes("foo a", fixes.iter()),
+            ("bar a;".to_string(), 2)
+        );
+    }
+
+    #[test]
+    fn fixpoint_removes_every_extra_semi() {
+        let store = CstRuleStore::new().builtins();
+        let res = fix_file(
+            0,
+            "function foo() {}\nfoo();;;\nexport class A { ; }",
+            true,
+            &store,
+            false,
+        )
+        .unwrap();
+        assert_eq!(res.fixed, "function foo() {}\nfoo();\nexport class A {  }");
+        assert!(res.result.diagnostics().next().is_none());
+    }
+
+    #[test]
+    fn builtin_rule_fixes() {
+        let store = CstRuleStore::new().builtins();
+        let fix = |src| fix_file(0, src, true, &store, false).unwrap().fixed;
+
+        assert_eq!(fix("if (!!foo) {}"), "if (foo) {}");
+        assert_eq!(fix("if (Boolean(a = b)) {}"), "if ((a = b)) {}");
+        assert_eq!(fix("!Boolean(a && b)"), "!(a && b)");
+        // replacing comparisons to `NaN` with `isNaN` would change the meaning of the code
+        assert_eq!(fix("if (foo == NaN) {}"), "if (foo == NaN) {}");
+        assert_eq!(fix("{ debugger; }"), "{  }");
+        assert_eq!(fix("if (foo) debugger;"), "if (foo) debugger;");
+    }
+}
@@ -0,0 +1,525 @@
+//! Building a [`ControlFlowGraph`] from the statements of a function or the root.
+
+use super::*;
+use rslint_parser::{ast::StmtListItem, AstNode, SyntaxKind::*, SyntaxNodeExt, T};
+
+/// A jump out of the current block which may need to go through enclosing statements to find its target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Jump {
+    Break(Option<String>),
+    Continue(Option<String>),
+    Return,
+    Throw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TryState {
+    Try,
+    Catch,
+}
+
+/// A statement which can be the target of a jump, or which changes where a jump goes.
+#[derive(Debug, Clone)]
+enum Context {
+    Loop {
+        label: Option<String>,
+        break_target: BlockId,
+        continue_target: BlockId,
+    },
+    /// A switch statement or a labelled statement which is not a loop.
+    Breakable {
+        label: Option<String>,
+        /// Whether an unlabelled `break` targets this statement.
+        unlabelled: bool,
+        break_target: BlockId,
+    },
+    Try {
+        state: TryState,
+        catch: Option<BlockId>,
+        finally: Option<BlockId>,
+        /// The jumps which went into the `finally` block and continue after it.
+        pending: Vec<Jump>,
+    },
+}
+
+/// An edge which only exists if one of some blocks is reachable, such as the edge from the end of a
+/// `finally` block to the statement after the `try`, which needs the `try` or `catch` block to complete normally.
+struct GuardedEdge {
+    from: BlockId,
+    to: BlockId,
+    guards: Vec<BlockId>,
+}
+
+struct CfgBuilder {
+    graph: ControlFlowGraph,
+    cur: BlockId,
+    contexts: Vec<Context>,
+    /// Edges which are added once the rest of the graph is built and the reachability of their guards is known.
+    guarded_edges: Vec<GuardedEdge>,
+    /// A label which applies to the next loop statement.
+    pending_label: Option<String>,
+}
+
+/// Build the graph of the top level statements of a `SCRIPT` or `MODULE`.
+pub(super) fn build_root(root: &SyntaxNode) -> ControlFlowGraph {
+    let mut builder = CfgBuilder::new(root.text_range());
+    builder.stmt_list(root.children());
+    builder.finish()
+}
+
+/// Build the graph of a function, arrow function, method, getter, or setter.
+pub(super) fn build_function(function: &SyntaxNode) -> ControlFlowGraph {
+    let mut builder = CfgBuilder::new(function.trimmed_range());
+    match function.child_with_kind(BLOCK_STMT) {
+        Some(body) => builder.stmt_list(body.children()),
+        // arrow functions with an expression body always return the expression
+        None => {
+            let exit = builder.graph.exit();
+            builder.edge(builder.cur, exit, EdgeKind::Return);
+            builder.cur = builder.new_block();
+        }
+    }
+    builder.finish()
+}
+
+fn label_text(node: &SyntaxNode) -> Option<String> {
+    node.child_with_kind(NAME_REF)
+        .map(|label| label.trimmed_text().to_string())
+}
+
+/// Whether a loop condition is always `true`, therefore the loop only exits with a jump.
+fn is_always_true(condition: Option<SyntaxNode>) -> bool {
+    match condition.and_then(|cond| cond.first_child()) {
+        Some(expr) => expr.kind() == LITERAL && expr.trimmed_text() == "true",
+        None => true,
+    }
+}
+
+impl CfgBuilder {
+    fn new(range: TextRange) -> Self {
+        let mut builder = Self {
+            graph: ControlFlowGraph {
+                range,
+                blocks: vec![],
+                reachable: vec![],
+                statements: HashMap::new(),
+                case_ends: HashMap::new(),
+            },
+            cur: BlockId(0),
+            contexts: vec![],
+            guarded_edges: vec![],
+            pending_label: None,
+        };
+        // the entry and the exit
+        builder.new_block();
+        builder.new_block();
+        builder
+    }
+
+    fn finish(mut self) -> ControlFlowGraph {
+        let exit = self.graph.exit();
+        self.edge(self.cur, exit, EdgeKind::Normal);
+
+        // adding a guarded edge can make the guards of other edges reachable, so repeat until nothing changes
+        self.graph.compute_reachability();
+        loop {
+            let graph = &self.graph;
+            let (ready, pending) = self.guarded_edges.drain(..).partition::<Vec<_>, _>(|edge| {
+                graph.is_reachable(edge.from)
+                    && edge.guards.iter().any(|guard| graph.is_reachable(*guard))
+            });
+            self.guarded_edges = pending;
+            if ready.is_empty() {
+                break;
+            }
+            for edge in ready {
+                self.edge(edge.from, edge.to, EdgeKind::Normal);
+            }
+            self.graph.compute_reachability();
+        }
+        self.graph
+    }
+
+    fn new_block(&mut self) -> BlockId {
+        self.graph.blocks.push(BasicBlock::default());
+        BlockId(self.graph.blocks.len() - 1)
+    }
+
+    fn edge(&mut self, from: BlockId, to: BlockId, kind: EdgeKind) {
+        self.graph.blocks[from.0]
+            .successors
+            .push(Edge { target: to, kind });
+        self.graph.blocks[to.0].predecessors.push(from);
+    }
+
+    /// End the current block with a jump, any statements after it are put in a new block without predecessors.
+    fn terminate(&mut self, node: &SyntaxNode, kind: TerminatorKind, jump: Jump) {
+        self.graph.blocks[self.cur.0].terminator = Some(Terminator {
+            kind,
+            range: node.trimmed_range(),
+        });
+        self.jump(self.cur, jump, self.contexts.len());
+        self.cur = self.new_block();
+    }
+
+    /// Connect a block to the target of a jump, only looking at `contexts[..depth]`.
+    fn jump(&mut self, from: BlockId, jump: Jump, depth: usize) {
+        for idx in (0..depth).rev() {
+            let target = match (&mut self.contexts[idx], &jump) {
+                (
+                    Context::Loop {
+                        label,
+                        break_target,
+                        ..
+                    },
+                    Jump::Break(target),
+                ) if target.is_none() || target == label => Some((*break_target, EdgeKind::Normal)),
+                (
+                    Context::Loop {
+                        label,
+                        continue_target,
+                        ..
+                    },
+                    Jump::Continue(target),
+                ) if target.is_none() || target == label => {
+                    Some((*continue_target, EdgeKind::Normal))
+                }
+                (
+                    Context::Breakable {
+                        label,
+                        unlabelled,
+                        break_target,
+                    },
+                    Jump::Break(target),
+                ) if (target.is_none() && *unlabelled) || (target.is_some() && target == label) => {
+                    Some((*break_target, EdgeKind::Normal))
+                }
+                (
+                    Context::Try {
+                        state: TryState::Try,
+                        catch: Some(catch),
+                        ..
+                    },
+                    Jump::Throw,
+                ) => Some((*catch, EdgeKind::Exception)),
+                (
+                    Context::Try {
+                        finally: Some(finally),
+                        pending,
+                        ..
+                    },
+                    _,
+                ) => {
+                    pending.push(jump.clone());
+                    Some((*finally, EdgeKind::Normal))
+                }
+                _ => None,
+            };
+
+            if let Some((target, kind)) = target {
+                self.edge(from, target, kind);
+                return;
+            }
+        }
+
+        let exit = self.graph.exit();
+        match jump {
+            Jump::Return => self.edge(from, exit, EdgeKind::Return),
+            Jump::Throw => self.edge(from, exit, EdgeKind::Throw),
+            // a break or continue without a target is a syntax error
+            _ => {}
+        }
+    }
+
+    fn stmt_list(&mut self, stmts: impl Iterator<Item = SyntaxNode>) {
+        for stmt in stmts.filter(|node| StmtListItem::can_cast(node.kind())) {
+            self.stmt(&stmt);
+        }
+    }
+
+    fn stmt(&mut self, node: &SyntaxNode) {
+        self.graph
+            .statements
+            .insert(node.trimmed_range().start().into(), self.cur);
+        self.graph.blocks[self.cur.0]
+            .statements
+            .push(node.trimmed_range());
+
+        let label = self.pending_label.take();
+        match node.kind() {
+            BLOCK_STMT => self.stmt_list(node.children()),
+            IF_STMT => self.if_stmt(node),
+            WHILE_STMT | DO_WHILE_STMT | FOR_STMT | FOR_IN_STMT | FOR_OF_STMT => {
+                self.loop_stmt(node, label)
+            }
+            SWITCH_STMT => self.switch_stmt(node, label),
+            TRY_STMT => self.try_stmt(node),
+            LABELLED_STMT => self.labelled_stmt(node),
+            WITH_STMT => {
+                if let Some(body) = node.children().filter(|n| n.kind() != CONDITION).last() {
+                    self.stmt(&body);
+                }
+            }
+            RETURN_STMT => self.terminate(node, TerminatorKind::Return, Jump::Return),
+            THROW_STMT => self.terminate(node, TerminatorKind::Throw, Jump::Throw),
+            BREAK_STMT => {
+                self.terminate(node, TerminatorKind::Break, Jump::Break(label_text(node)))
+            }
+            CONTINUE_STMT => self.terminate(
+                node,
+                TerminatorKind::Continue,
+                Jump::Continue(label_text(node)),
+            ),
+            _ => {}
+        }
+    }
+
+    /// The statement children of a node, which excludes conditions and loop heads.
+    fn body_stmts(node: &SyntaxNode) -> impl Iterator<Item = SyntaxNode> {
+        node.children()
+            .filter(|child| StmtListItem::can_cast(child.kind()))
+    }
+
+    fn if_stmt(&mut self, node: &SyntaxNode) {
+        let mut bodies = Self::body_stmts(node);
+        let cons = bodies.next();
+        let alt = bodies.next();
+        let cond = self.cur;
+        let after = self.new_block();
+
+        for (body, kind) in [(cons, EdgeKind::True), (alt, EdgeKind::False)] {
+            match body {
+                Some(body) => {
+                    self.cur = self.new_block();
+                    self.edge(cond, self.cur, kind);
+                    self.stmt(&body);
+                    self.edge(self.cur, after, EdgeKind::Normal);
+                }
+                None => self.edge(cond, after, kind),
+            }
+        }
+        self.cur = after;
+    }
+
+    fn loop_stmt(&mut self, node: &SyntaxNode, label: Option<String>) {
+        let body = Self::body_stmts(node).next();
+        let kind = node.kind();
+        let after = self.new_block();
+        let header = self.new_block();
+        // the block `continue` jumps to, which is the test for `do while` and the update for `for`
+        let continue_target = match kind {
+            DO_WHILE_STMT | FOR_STMT => self.new_block(),
+            _ => header,
+        };
+
+        let infinite = match kind {
+            WHILE_STMT | DO_WHILE_STMT => is_always_true(node.child_with_kind(CONDITION)),
+            FOR_STMT => is_always_true(node.child_with_kind(FOR_STMT_TEST)),
+            _ => false,
+        };
+
+        let body_block = self.new_block();
+        if kind == DO_WHILE_STMT {
+            self.edge(self.cur, body_block, EdgeKind::Normal);
+            self.edge(continue_target, body_block, EdgeKind::True);
+            if !infinite {
+                self.edge(continue_target, after, EdgeKind::False);
+            }
+        } else {
+            self.edge(self.cur, header, EdgeKind::Normal);
+            self.edge(header, body_block, EdgeKind::True);
+            if !infinite {
+                self.edge(header, after, EdgeKind::False);
+            }
+            if kind == FOR_STMT {
+                self.edge(continue_target, header, EdgeKind::Normal);
+            }
+        }
+
+        self.contexts.push(Context::Loop {
+            label,
+            break_target: after,
+            continue_target,
+        });
+        self.cur = body_block;
+        if let Some(body) = body {
+            self.stmt(&body);
+        }
+        self.contexts.pop();
+        self.edge(self.cur, continue_target, EdgeKind::Normal);
+        self.cur = after;
+    }
+
+    fn switch_stmt(&mut self, node: &SyntaxNode, label: Option<String>) {
+        let dispatch = self.cur;
+        let after = self.new_block();
+        self.contexts.push(Context::Breakable {
+            label,
+            unlabelled: true,
+            break_target: after,
+        });
+
+        let clauses = node
+            .children()
+            .filter(|child| matches!(child.kind(), CASE_CLAUSE | DEFAULT_CLAUSE))
+            .collect::<Vec<_>>();
+
+        let mut prev_end: Option<BlockId> = None;
+        for clause in &clauses {
+            let block = self.new_block();
+            self.edge(dispatch, block, EdgeKind::True);
+            if let Some(prev) = prev_end {
+                self.edge(prev, block, EdgeKind::Normal);
+            }
+            self.cur = block;
+
+            // the test of a case clause comes before the colon and is not a statement
+            let colon = clause
+                .children_with_tokens()
+                .find(|elem| elem.kind() == T![:])
+                .map(|colon| colon.text_range().end());
+            self.stmt_list(
+                clause
+                    .children()
+                    .filter(|child| colon.map_or(false, |end| child.text_range().start() >= end)),
+            );
+
+            self.graph
+                .case_ends
+                .insert(clause.trimmed_range().start().into(), self.cur);
+            prev_end = Some(self.cur);
+        }
+
+        if let Some(last) = prev_end {
+            self.edge(last, after, EdgeKind::Normal);
+        }
+        if !clauses.iter().any(|clause| clause.kind() == DEFAULT_CLAUSE) {
+            self.edge(dispatch, after, EdgeKind::False);
+        }
+        self.contexts.pop();
+        self.cur = after;
+    }
+
+    fn labelled_stmt(&mut self, node: &SyntaxNode) {
+        let label = node
+            .child_with_kind(NAME)
+            .map(|name| name.trimmed_text().to_string());
+        let body = match Self::body_stmts(node).next() {
+            Some(body) => body,
+            None => return,
+        };
+
+        if matches!(
+            body.kind(),
+            WHILE_STMT | DO_WHILE_STMT | FOR_STMT | FOR_IN_STMT | FOR_OF_STMT | SWITCH_STMT
+        ) {
+            self.pending_label = label;
+            self.stmt(&body);
+        } else {
+            let after = self.new_block();
+            self.contexts.push(Context::Breakable {
+                label,
+                unlabelled: false,
+                break_target: after,
+            });
+            self.stmt(&body);
+            self.contexts.pop();
+            self.edge(self.cur, after, EdgeKind::Normal);
+            self.cur = after;
+        }
+    }
+
+    fn try_stmt(&mut self, node: &SyntaxNode) {
+        let body = node.child_with_kind(BLOCK_STMT);
+        let handler = node
+            .child_with_kind(CATCH_CLAUSE)
+            .and_then(|clause| clause.child_with_kind(BLOCK_STMT));
+        let finalizer = node
+            .child_with_kind(FINALIZER)
+            .and_then(|clause| clause.child_with_kind(BLOCK_STMT));
+
+        let catch = handler.as_ref().map(|_| self.new_block());
+        let finally = finalizer.as_ref().map(|_| self.new_block());
+        let after = self.new_block();
+        self.contexts.push(Context::Try {
+            state: TryState::Try,
+            catch,
+            finally,
+            pending: vec![],
+        });
+
+        // any statement inside of the try block may throw
+        let first_block = self.graph.blocks.len();
+        let try_start = self.cur;
+        if let Some(body) = &body {
+            self.stmt(body);
+        }
+        let try_blocks = std::iter::once(try_start)
+            .chain((first_block..self.graph.blocks.len()).map(BlockId))
+            .collect::<Vec<_>>();
+        let mut normal_ends = vec![self.cur];
+
+        match (catch, finally) {
+            (Some(catch), _) => {
+                for block in try_blocks {
+                    self.edge(block, catch, EdgeKind::Exception);
+                }
+            }
+            (None, Some(finally)) => {
+                for block in try_blocks {
+                    self.edge(block, finally, EdgeKind::Exception);
+                }
+                if let Some(Context::Try { pending, .. }) = self.contexts.last_mut() {
+                    pending.push(Jump::Throw);
+                }
+            }
+            _ => {}
+        }
+
+        if let (Some(catch), Some(handler)) = (catch, &handler) {
+            if let Some(Context::Try { state, .. }) = self.contexts.last_mut() {
+                *state = TryState::Catch;
+            }
+            self.cur = catch;
+            self.stmt(handler);
+            normal_ends.push(self.cur);
+        }
+
+        let pending = match self.contexts.pop() {
+            Some(Context::Try { pending, .. }) => pending,
+            _ => unreachable!("the try context is always at the top of the stack"),
+        };
+
+        match (finally, &finalizer) {
+            (Some(finally), Some(finalizer)) => {
+                for end in &normal_ends {
+                    self.edge(*end, finally, EdgeKind::Normal);
+                }
+                self.cur = finally;
+                self.stmt(finalizer);
+                let finally_end = self.cur;
+
+                // jumps which went through the finally block continue to their original target
+                let mut seen = vec![];
+                for jump in pending {
+                    if !seen.contains(&jump) {
+                        self.jump(finally_end, jump.clone(), self.contexts.len());
+                        seen.push(jump);
+                    }
+                }
+                // the finally block only completes normally if the try or catch block does
+                self.guarded_edges.push(GuardedEdge {
+                    from: finally_end,
+                    to: after,
+                    guards: normal_ends,
+                });
+            }
+            _ => {
+                for end in normal_ends {
+                    self.edge(end, after, EdgeKind::Normal);
+                }
+            }
+        }
+        self.cur = after;
+    }
+}
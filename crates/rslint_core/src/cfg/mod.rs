@@ -0,0 +1,400 @@
+//! Control flow graphs of the functions and the root of a file.
+//!
+//! A graph is built for every function, arrow function, method, getter, setter, and for the top level
+//! statements of the file. Each graph is made of basic blocks which contain statements, blocks are connected
+//! by edges for conditions, loops, switch cases, try/catch/finally, labels, `break`, `continue`, `return` and `throw`.
+//! Graphs are computed once per file by the runner and are shared by every rule through [`RuleCtx::cfg`](crate::RuleCtx::cfg).
+//!
+//! The graphs work at the statement level, expressions such as `a && b` do not create blocks.
+//! Just like the scope analysis, statements and functions are referred to by their ranges.
+
+mod builder;
+
+use crate::Span;
+use rslint_parser::{SyntaxKind, SyntaxNode, TextRange};
+use std::collections::HashMap;
+
+/// The id of a basic block inside of a [`ControlFlowGraph`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockId(pub usize);
+
+/// The kind of an edge between two basic blocks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// Unconditional control flow, such as the next statement or a `break`.
+    Normal,
+    /// Taken if a condition is truthy.
+    True,
+    /// Taken if a condition is falsey.
+    False,
+    /// Taken if a statement inside of a `try` block throws.
+    Exception,
+    /// A `return` statement, or the body of an arrow function which returns an expression.
+    Return,
+    /// A `throw` statement which is not caught inside of the function.
+    Throw,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub target: BlockId,
+    pub kind: EdgeKind,
+}
+
+/// The kind of statement which unconditionally jumps out of a block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TerminatorKind {
+    Return,
+    Throw,
+    Break,
+    Continue,
+}
+
+/// A statement which unconditionally jumps out of a block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Terminator {
+    pub kind: TerminatorKind,
+    pub range: TextRange,
+}
+
+/// A sequence of statements which are always run one after another.
+#[derive(Debug, Clone, Default)]
+pub struct BasicBlock {
+    /// The ranges of the statements in the block in order. Compound statements such as `if` are part
+    /// of the block their condition is run in, their bodies are in separate blocks.
+    pub statements: Vec<TextRange>,
+    pub successors: Vec<Edge>,
+    pub predecessors: Vec<BlockId>,
+    /// The statement which ends this block by jumping out of it, if there is one.
+    pub terminator: Option<Terminator>,
+}
+
+/// The control flow graph of a single function or the root of a file.
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    /// The range of the function or the root node.
+    pub range: TextRange,
+    pub blocks: Vec<BasicBlock>,
+    reachable: Vec<bool>,
+    statements: HashMap<usize, BlockId>,
+    case_ends: HashMap<usize, BlockId>,
+}
+
+impl ControlFlowGraph {
+    /// The block control flow starts in.
+    pub fn entry(&self) -> BlockId {
+        BlockId(0)
+    }
+
+    /// The block which every return, uncaught throw, and the end of the function lead to.
+    /// This block never contains any statements.
+    pub fn exit(&self) -> BlockId {
+        BlockId(1)
+    }
+
+    pub fn block(&self, id: BlockId) -> &BasicBlock {
+        &self.blocks[id.0]
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = (BlockId, &BasicBlock)> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .map(|(idx, block)| (BlockId(idx), block))
+    }
+
+    /// Whether there is a path from the entry of the graph to a block.
+    pub fn is_reachable(&self, block: BlockId) -> bool {
+        self.reachable[block.0]
+    }
+
+    /// Get the block a statement is in, or `None` if the range is not a statement of this graph.
+    pub fn block_of(&self, statement: impl Span) -> Option<BlockId> {
+        self.statements.get(&statement.as_range().start).copied()
+    }
+
+    /// Whether a statement of this graph can be run.
+    pub fn is_statement_reachable(&self, statement: impl Span) -> Option<bool> {
+        self.block_of(statement)
+            .map(|block| self.is_reachable(block))
+    }
+
+    /// Whether the end of a `CASE_CLAUSE` or `DEFAULT_CLAUSE` can be reached, meaning control flow
+    /// falls through to the next clause, or out of the switch if it is the last clause.
+    pub fn case_end_reachable(&self, clause: impl Span) -> Option<bool> {
+        self.case_ends
+            .get(&clause.as_range().start)
+            .map(|block| self.is_reachable(*block))
+    }
+
+    /// Whether control flow can reach the end of the function without a `return` or `throw`.
+    pub fn completes_normally(&self) -> bool {
+        self.reachable_edges_to(self.exit())
+            .any(|edge| edge.kind == EdgeKind::Normal)
+    }
+
+    /// Edges from reachable blocks which lead to a block.
+    pub fn reachable_edges_to(&self, target: BlockId) -> impl Iterator<Item = &Edge> {
+        self.block(target)
+            .predecessors
+            .iter()
+            .filter(move |pred| self.is_reachable(**pred))
+            .flat_map(move |pred| self.block(*pred).successors.iter())
+            .filter(move |edge| edge.target == target)
+    }
+
+    fn compute_reachability(&mut self) {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = vec![self.entry()];
+        while let Some(block) = stack.pop() {
+            if reachable[block.0] {
+                continue;
+            }
+            reachable[block.0] = true;
+            stack.extend(
+                self.blocks[block.0]
+                    .successors
+                    .iter()
+                    .map(|edge| edge.target),
+            );
+        }
+        self.reachable = reachable;
+    }
+}
+
+/// The control flow graphs of every function in a file, as well as the root.
+#[derive(Debug, Clone)]
+pub struct ControlFlow {
+    /// The root graph followed by the graphs of functions in source order, sorted by their start.
+    graphs: Vec<ControlFlowGraph>,
+    /// The index of the graph each graph is nested in, the root graph is its own parent.
+    parents: Vec<usize>,
+}
+
+/// The kinds of nodes which get their own control flow graph.
+pub const FUNCTION_KINDS: [SyntaxKind; 6] = [
+    SyntaxKind::FN_DECL,
+    SyntaxKind::FN_EXPR,
+    SyntaxKind::ARROW_EXPR,
+    SyntaxKind::METHOD,
+    SyntaxKind::GETTER,
+    SyntaxKind::SETTER,
+];
+
+impl ControlFlow {
+    /// Build the graphs of a `SCRIPT` or `MODULE` root node and every function inside of it.
+    pub fn new(root: &SyntaxNode) -> Self {
+        let graphs = std::iter::once(builder::build_root(root))
+            .chain(
+                root.descendants()
+                    .filter(|node| FUNCTION_KINDS.contains(&node.kind()))
+                    .map(|node| builder::build_function(&node)),
+            )
+            .collect::<Vec<_>>();
+
+        // functions come in preorder, so the graph a function is nested in is the last
+        // graph before it which has not ended yet.
+        let mut parents = vec![0; graphs.len()];
+        let mut stack = vec![0];
+        for (idx, graph) in graphs.iter().enumerate().skip(1) {
+            while let Some(&parent) = stack.last() {
+                if parent == 0 || graphs[parent].range.contains_range(graph.range) {
+                    parents[idx] = parent;
+                    break;
+                }
+                stack.pop();
+            }
+            stack.push(idx);
+        }
+
+        Self { graphs, parents }
+    }
+
+    /// The graph of the top level statements of the file.
+    pub fn root(&self) -> &ControlFlowGraph {
+        &self.graphs[0]
+    }
+
+    /// All of the graphs in the file, starting with the root graph.
+    pub fn graphs(&self) -> impl Iterator<Item = &ControlFlowGraph> {
+        self.graphs.iter()
+    }
+
+    /// Get the graph of a function node, the node must be one of [`FUNCTION_KINDS`].
+    pub fn function(&self, function: impl Span) -> Option<&ControlFlowGraph> {
+        let start = function.as_range().start;
+        self.graphs[1..]
+            .binary_search_by_key(&start, |graph| usize::from(graph.range.start()))
+            .ok()
+            .map(|idx| &self.graphs[idx + 1])
+    }
+
+    /// Get the innermost graph which contains a range, this is the root graph if the range is not inside of a function.
+    pub fn graph_containing(&self, span: impl Span) -> &ControlFlowGraph {
+        let range = span.as_range();
+        // the last graph starting before the range contains it, or one of the graphs it is nested in does
+        let mut idx = self.graphs[1..]
+            .partition_point(|graph| usize::from(graph.range.start()) <= range.start);
+        while idx != 0 && range.end > usize::from(self.graphs[idx].range.end()) {
+            idx = self.parents[idx];
+        }
+        &self.graphs[idx]
+    }
+
+    /// Whether a statement can be run, or `None` if the range is not a statement.
+    pub fn is_statement_reachable(&self, statement: impl Span) -> Option<bool> {
+        let range = statement.as_range();
+        self.graph_containing(range.clone())
+            .is_statement_reachable(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_parser::{parse_module, SyntaxNodeExt};
+
+    fn reachability(code: &str) -> Vec<(String, bool)> {
+        let root = parse_module(code, 0).syntax();
+        let cfg = ControlFlow::new(&root);
+        root.descendants()
+            .filter(|node| node.kind() == SyntaxKind::EXPR_STMT)
+            .map(|node| {
+                (
+                    node.trimmed_text().to_string(),
+                    cfg.is_statement_reachable(&node).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn unreachable(code: &str) -> Vec<String> {
+        reachability(code)
+            .into_iter()
+            .filter(|(_, reachable)| !reachable)
+            .map(|(text, _)| text)
+            .collect()
+    }
+
+    #[test]
+    fn jumps() {
+        assert_eq!(
+            unreachable("function f() { a; return; b; } c; throw d; e;"),
+            vec!["b;", "e;"]
+        );
+        assert_eq!(
+            unreachable("while (x) { a; break; b; } c; for (;;) { d; continue; e; } f;"),
+            vec!["b;", "e;", "f;"]
+        );
+        assert_eq!(
+            unreachable("if (x) { return; } else { throw y; } a;"),
+            vec!["a;"]
+        );
+        assert_eq!(unreachable("if (x) { return; } a;"), Vec::<String>::new());
+        assert_eq!(
+            unreachable("outer: while (x) { while (true) { break outer; a; } b; } c;"),
+            vec!["a;", "b;"]
+        );
+        assert_eq!(
+            unreachable("block: { break block; a; } b; do { continue; c; } while (x); d;"),
+            vec!["a;", "c;"]
+        );
+    }
+
+    #[test]
+    fn try_catch_finally() {
+        assert_eq!(
+            unreachable("try { throw a; b; } catch (e) { c; } d;"),
+            vec!["b;"]
+        );
+        assert_eq!(unreachable("try { return; } finally { a; } b;"), vec!["b;"]);
+        assert_eq!(
+            unreachable("try { a; } catch { return; } finally { b; } c;"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            unreachable("while (x) { try { break; } finally { a; } b; } c;"),
+            vec!["b;"]
+        );
+        // the exit edge of a `do while` is added before its condition block has any predecessors
+        assert_eq!(
+            unreachable("do { a; } while (x);\ntry { b; } finally { c; }\nd;"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            unreachable("try { try { return; } finally { a; } } finally { b; } c;"),
+            vec!["c;"]
+        );
+    }
+
+    #[test]
+    fn switch_cases() {
+        let code = "switch (x) { case 1: a; case 2: b; break; case 3: return; default: c; }";
+        let root = parse_module(code, 0).syntax();
+        let cfg = ControlFlow::new(&root);
+        let ends = root
+            .descendants()
+            .filter(|node| {
+                matches!(
+                    node.kind(),
+                    SyntaxKind::CASE_CLAUSE | SyntaxKind::DEFAULT_CLAUSE
+                )
+            })
+            .map(|clause| cfg.root().case_end_reachable(&clause).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ends, vec![true, false, false, true]);
+        assert!(unreachable(code).is_empty());
+    }
+
+    #[test]
+    fn function_completion() {
+        let code = "
+            function a() { if (x) { return 1; } }
+            function b() { if (x) { return 1; } else { throw 2; } }
+            function c() { while (true) {} }
+            function d() { switch (x) { case 1: return 1; default: return 2; } }
+            const e = () => 5;
+        ";
+        let root = parse_module(code, 0).syntax();
+        let cfg = ControlFlow::new(&root);
+        let completes = root
+            .descendants()
+            .filter(|node| FUNCTION_KINDS.contains(&node.kind()))
+            .map(|func| cfg.function(&func).unwrap().completes_normally())
+            .collect::<Vec<_>>();
+        assert_eq!(completes, vec![true, false, false, false, false]);
+    }
+
+    #[test]
+    fn innermost_graph() {
+        let code = "
+            function a() {
+                const b = () => { one; };
+                two;
+            }
+            three;
+            function c() { four; }
+        ";
+        let root = parse_module(code, 0).syntax();
+        let cfg = ControlFlow::new(&root);
+        let graphs = root
+            .descendants()
+            .filter(|node| node.kind() == SyntaxKind::EXPR_STMT)
+            .map(|node| {
+                let range = cfg.graph_containing(&node).range;
+                root.covering_element(range)
+                    .into_node()
+                    .map_or(SyntaxKind::MODULE, |node| node.kind())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            graphs,
+            vec![
+                SyntaxKind::ARROW_EXPR,
+                SyntaxKind::FN_DECL,
+                SyntaxKind::MODULE,
+                SyntaxKind::FN_DECL
+            ]
+        );
+    }
+}
@@ -1,13 +1,14 @@
 //! A simple builder for facilitating the creation of diagnostics
 
-use crate::{Diagnostic, RuleResult, SyntaxNode};
+use crate::{autofix::Fixer, Diagnostic, RuleResult, SyntaxNode};
 use codespan_reporting::diagnostic::{Label, Severity};
 use rslint_parser::{SyntaxElement, SyntaxNodeExt, SyntaxToken, TextRange};
 use std::ops::Range;
 
-/// A simple builder for creating codespan diagnostics sequentially
+/// A simple builder for creating codespan diagnostics sequentially.
+/// The builder also carries an optional fix for the diagnostic.
 #[derive(Debug, Clone)]
-pub struct DiagnosticBuilder(Diagnostic, usize);
+pub struct DiagnosticBuilder(Diagnostic, usize, Option<Fixer>);
 
 impl DiagnosticBuilder {
     /// Create a new builder with a severity of error
@@ -21,6 +22,7 @@ impl DiagnosticBuilder {
                 notes: vec![],
             },
             file_id,
+            None,
         )
     }
 
@@ -35,6 +37,7 @@ impl DiagnosticBuilder {
                 notes: vec![],
             },
             file_id,
+            None,
         )
     }
 
@@ -53,6 +56,7 @@ impl DiagnosticBuilder {
                 notes: vec![],
             },
             file_id,
+            None,
         )
     }
 
@@ -84,9 +88,35 @@ impl DiagnosticBuilder {
         self
     }
 
+    /// Attach a fix to this diagnostic, if the diagnostic already has a fix, the edits are merged.
+    pub fn fix(mut self, fixer: Fixer) -> Self {
+        match self.2.as_mut() {
+            Some(existing) => existing.indels.extend(fixer.indels),
+            None => self.2 = Some(fixer).filter(|fixer| !fixer.is_empty()),
+        }
+        self
+    }
+
+    /// The fix attached to this diagnostic, if any.
+    pub fn fixer(&self) -> Option<&Fixer> {
+        self.2.as_ref()
+    }
+
     pub fn finish(self) -> Diagnostic {
         self.0
     }
+
+    /// Finish the diagnostic, returning it along with its fix.
+    pub fn finish_with_fix(self) -> (Diagnostic, Option<Fixer>) {
+        (self.0, self.2)
+    }
+}
+
+impl From<Diagnostic> for DiagnosticBuilder {
+    fn from(diagnostic: Diagnostic) -> DiagnosticBuilder {
+        let file_id = diagnostic.labels.first().map_or(0, |label| label.file_id);
+        Self(diagnostic, file_id, None)
+    }
 }
 
 impl From<DiagnosticBuilder> for Diagnostic {
@@ -97,17 +127,17 @@ impl From<DiagnosticBuilder> for Diagnostic {
 
 impl From<DiagnosticBuilder> for RuleResult {
     fn from(builder: DiagnosticBuilder) -> RuleResult {
+        let (diagnostic, fix) = builder.finish_with_fix();
         RuleResult {
-            diagnostics: vec![builder.into()],
+            diagnostics: vec![diagnostic],
+            fixes: fix.into_iter().collect(),
         }
     }
 }
 
 impl From<DiagnosticBuilder> for Option<RuleResult> {
     fn from(builder: DiagnosticBuilder) -> Option<RuleResult> {
-        Some(RuleResult {
-            diagnostics: vec![builder.into()],
-        })
+        Some(builder.into())
     }
 }
 
@@ -11,18 +11,26 @@
 //!   +-------------------------------------------------------------------------------+
 //!                                      Directive
 //! ```
+//!
+//! Block comments declaring globals, such as `/* global foo, bar: writable */`, are also directives.
+//! They can be anywhere in the file and they add globals to the entire file.
 
 mod parser;
 
 pub use self::parser::*;
 
-use crate::{rule_tests, CstRule, CstRuleStore, Diagnostic, DiagnosticBuilder, SyntaxNode};
+use crate::{
+    rule_tests, CstRule, CstRuleStore, Diagnostic, DiagnosticBuilder, RuleLevel, SyntaxNode,
+};
 use rslint_parser::util::*;
 
 // TODO: More complex warnings, things like ignoring node directives because of file level directives
 
 /// Apply file level directives to a store and add their respective diagnostics to the pool of diagnostics.
-/// for file level ignores this will clear all the rules from the store.
+/// for file level ignores this will clear all the rules from the store, `/* global */` comments
+/// are added to the globals of the store, and `level` commands set the level of rules in the store.
+/// A rule which is not in the store is added with its default config if it is given a level other than `off`,
+/// ignored rules are never run regardless of their level.
 ///
 /// This method furthermore issues more contextual warnings like disabling a rule after
 /// the entire file has been disabled.
@@ -33,6 +41,7 @@ pub fn apply_top_level_directives(
     file_id: usize,
 ) {
     let mut ignored = Vec::new();
+    let mut ignored_rules = Vec::new();
     let mut cleared = None;
 
     for directive in directives {
@@ -40,14 +49,24 @@ pub fn apply_top_level_directives(
             if command.top_level() {
                 match command {
                     Command::IgnoreFile => {
-                        store.rules.clear();
                         cleared = Some(directive.comment.token.text_range());
                     }
                     Command::IgnoreRulesFile(rules) => {
                         ignored.push(directive.comment.token.text_range());
-                        store.rules.retain(|rule| {
-                            !rules.iter().any(|allowed| allowed.name() == rule.name())
-                        });
+                        ignored_rules.extend(rules.iter().map(|rule| rule.name()));
+                    }
+                    Command::Globals(globals) => {
+                        for (name, kind) in globals {
+                            store.globals.insert(name.as_str(), *kind);
+                        }
+                    }
+                    Command::RuleLevels(levels) => {
+                        for (rule, level) in levels {
+                            if *level != RuleLevel::Off && store.get(rule.name()).is_none() {
+                                store.rules.push(rule.clone());
+                            }
+                            store.set_level(rule.name(), *level);
+                        }
                     }
                     _ => unreachable!(),
                 }
@@ -55,6 +74,13 @@ pub fn apply_top_level_directives(
         }
     }
 
+    if cleared.is_some() {
+        store.rules.clear();
+    }
+    store
+        .rules
+        .retain(|rule| !ignored_rules.contains(&rule.name()));
+
     if let Some(range) = cleared {
         for ignored_range in ignored {
             let warn = DiagnosticBuilder::warning(
@@ -143,3 +169,56 @@ rule_tests! {
         "
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::groups::errors::{NoDebugger, NoEmpty, NoUndef, NoUnusedVars};
+    use crate::{lint_file, CstRuleStore, Severity};
+
+    #[test]
+    fn level_directives() {
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![Box::new(NoEmpty::default()) as Box<_>]);
+        let source =
+            "// rslint-level no-empty: info, no-debugger: warn -- context\n\n{}\ndebugger;";
+        let result = lint_file(0, source, false, &store, false).unwrap();
+        assert!(result.directive_diagnostics.is_empty());
+        assert_eq!(
+            result.rule_results["no-empty"].diagnostics[0].severity,
+            Severity::Note
+        );
+        // rules which are not in the store are run at the level they are given
+        assert_eq!(
+            result.rule_results["no-debugger"].diagnostics[0].severity,
+            Severity::Warning
+        );
+
+        store.load_rules(vec![Box::new(NoDebugger::default()) as Box<_>]);
+        let source = "// rslint-level no-debugger: off\n\ndebugger;";
+        let result = lint_file(0, source, false, &store, false).unwrap();
+        assert!(!result.rule_results.contains_key("no-debugger"));
+    }
+
+    fn diagnostic_count(rule: &str, source: &str) -> usize {
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![
+            Box::new(NoUnusedVars::default()) as Box<_>,
+            Box::new(NoUndef::default()) as Box<_>,
+        ]);
+        let result = lint_file(0, source, false, &store, false).unwrap();
+        result.rule_results[rule].diagnostics.len()
+    }
+
+    #[test]
+    fn ignore_directives_of_root_rules() {
+        let unused = "function f() {\n let foo = 5;\n let bar = 6;\n}\nf();";
+        assert_eq!(diagnostic_count("no-unused-vars", unused), 2);
+        let unused = "function f() {\n // rslint-ignore no-unused-vars\n let foo = 5;\n let bar = 6;\n}\nf();";
+        assert_eq!(diagnostic_count("no-unused-vars", unused), 1);
+
+        let undefined = "function f() {\n foo();\n bar();\n}\nf();";
+        assert_eq!(diagnostic_count("no-undef", undefined), 2);
+        let undefined = "function f() {\n // rslint-ignore no-undef\n foo();\n bar();\n}\nf();";
+        assert_eq!(diagnostic_count("no-undef", undefined), 1);
+    }
+}
@@ -1,13 +1,20 @@
-use crate::{util::find_best_match_for_name, CstRule, CstRuleStore, Diagnostic, DiagnosticBuilder};
+use crate::{
+    globals::GlobalKind, util::find_best_match_for_name, CstRule, CstRuleStore, Diagnostic,
+    DiagnosticBuilder, RuleLevel,
+};
 use codespan_reporting::diagnostic::Severity;
 use rslint_lexer::Lexer as RawLexer;
 use rslint_parser::{
-    util::Comment, SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTokenExt, TextRange, T,
+    util::{Comment, CommentKind},
+    SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTokenExt, TextRange, T,
 };
 use std::collections::HashMap;
 use std::iter::Peekable;
 use std::ops::Range;
 
+/// A rule and the level it is given by a `level` command.
+pub type RuleLevelPair = (Box<dyn CstRule>, RuleLevel);
+
 pub struct DirectiveParseResult {
     pub diagnostics: Vec<Diagnostic>,
     pub directive: Directive,
@@ -25,12 +32,22 @@ pub enum Command {
     IgnoreNode(TextRange),
     /// Ignore rules for an entire file.
     IgnoreRulesFile(Vec<Box<dyn CstRule>>),
+    /// Declare globals for an entire file using a `/* global foo, bar: writable */` comment.
+    Globals(Vec<(String, GlobalKind)>),
+    /// Set the level of rules for an entire file.
+    RuleLevels(Vec<RuleLevelPair>),
 }
 
 impl Command {
     /// Whether this command applies to the entire file.
     pub fn top_level(&self) -> bool {
-        matches!(self, Command::IgnoreFile | Command::IgnoreRulesFile(_))
+        matches!(
+            self,
+            Command::IgnoreFile
+                | Command::IgnoreRulesFile(_)
+                | Command::Globals(_)
+                | Command::RuleLevels(_)
+        )
     }
 }
 
@@ -45,6 +62,8 @@ pub struct Directive {
 
 struct RawCommand {
     tokens: Vec<Token>,
+    /// The level of each rule of a `level` command.
+    levels: Vec<RuleLevel>,
     // partially incomplete (rule vectors)
     kind: Command,
 }
@@ -96,9 +115,82 @@ impl<'store> DirectiveParser<'store> {
         Ok(raw
             .into_iter()
             .map(|raw| self.bake_raw_directive(raw))
+            .chain(self.extract_global_directives())
             .collect())
     }
 
+    /// Extract `/* global foo */` or `/* globals foo */` comments, which can be anywhere in the file.
+    fn extract_global_directives(&self) -> Vec<DirectiveParseResult> {
+        self.root_node
+            .descendants_with_tokens()
+            .filter_map(|elem| elem.into_token()?.comment())
+            .filter(|comment| comment.kind != CommentKind::Inline)
+            .filter_map(|comment| self.parse_global_comment(comment))
+            .collect()
+    }
+
+    /// Parse a comma separated list of globals, each global can optionally have a kind after a colon,
+    /// which is `readonly` if it is omitted. Anything after `--` is a description and it is ignored.
+    fn parse_global_comment(&self, comment: Comment) -> Option<DirectiveParseResult> {
+        // `Comment::content` skips the first character of `/*` comments, so the text is taken from the token
+        let text = comment.token.text().as_str();
+        let content = text[2..].strip_suffix("*/").unwrap_or(&text[2..]);
+        let trimmed = content.trim_start();
+        let list = trimmed
+            .strip_prefix("globals")
+            .or_else(|| trimmed.strip_prefix("global"))
+            .filter(|rest| rest.starts_with(char::is_whitespace))?
+            .split("--")
+            .next()
+            .unwrap();
+
+        let token_start = usize::from(comment.token.text_range().start());
+        let offset_of =
+            |slice: &str| token_start + (slice.as_ptr() as usize - text.as_ptr() as usize);
+
+        let mut globals = vec![];
+        let mut diagnostics = vec![];
+        for part in list.split(',') {
+            let (name, value) = match part.find(':') {
+                Some(idx) => (&part[..idx], Some(part[idx + 1..].trim())),
+                None => (part, None),
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+
+            let kind = match value {
+                None | Some("readonly") | Some("readable") | Some("false") => GlobalKind::Readonly,
+                Some("writable") | Some("writeable") | Some("true") => GlobalKind::Writable,
+                Some("off") => GlobalKind::Off,
+                Some(value) => {
+                    let start = offset_of(value);
+                    let err = self
+                        .err(format!(
+                            "invalid kind `{}` for the global `{}`",
+                            value, name
+                        ))
+                        .primary(start..start + value.len(), "")
+                        .note(
+                            "help: the kind of a global must be `readonly`, `writable`, or `off`",
+                        );
+                    diagnostics.push(err.into());
+                    continue;
+                }
+            };
+            globals.push((name.to_string(), kind));
+        }
+
+        Some(DirectiveParseResult {
+            diagnostics,
+            directive: Directive {
+                commands: vec![Command::Globals(globals)],
+                comment,
+            },
+        })
+    }
+
     fn err(&self, message: impl AsRef<str>) -> DiagnosticBuilder {
         DiagnosticBuilder::error(self.file_id, "directives", message.as_ref())
     }
@@ -108,10 +200,20 @@ impl<'store> DirectiveParser<'store> {
         let mut commands = vec![];
 
         for raw_command in directive.commands.into_iter() {
+            if let Command::RuleLevels(_) = raw_command.kind {
+                let (diags, levels) = self.bake_level_command(&raw_command);
+                diagnostics.extend(diags);
+                commands.push(Command::RuleLevels(levels));
+                continue;
+            }
+
             let (diags, rules) = self.bake_ignore_command(&raw_command);
             diagnostics.extend(diags);
             let command = match raw_command.kind {
-                Command::IgnoreFile | Command::IgnoreNode(_) => raw_command.kind,
+                Command::IgnoreFile
+                | Command::IgnoreNode(_)
+                | Command::Globals(_)
+                | Command::RuleLevels(_) => raw_command.kind,
                 Command::IgnoreRules(_, node) => Command::IgnoreRules(rules, node),
                 Command::IgnoreRulesFile(_) => Command::IgnoreRulesFile(rules),
             };
@@ -168,27 +270,44 @@ impl<'store> DirectiveParser<'store> {
                     rules.push(rule);
                 }
             } else {
-                let mut err = self
-                    .err(format!("unknown rule `{}` used in directive", raw))
-                    .primary(range.to_owned(), "");
-
-                if let Some(suggestion) = find_best_match_for_name(
-                    CstRuleStore::new()
-                        .builtins()
-                        .rules
-                        .iter()
-                        .map(|x| x.name()),
-                    raw,
-                    None,
-                ) {
-                    err = err.note(format!("help: did you mean `{}`?", suggestion));
-                }
-                diagnostics.push(err.into());
+                diagnostics.push(self.unknown_rule(raw, range));
             }
         }
         (diagnostics, rules)
     }
 
+    fn bake_level_command(&self, command: &RawCommand) -> (Vec<Diagnostic>, Vec<RuleLevelPair>) {
+        let mut diagnostics = vec![];
+        let mut levels = Vec::with_capacity(command.tokens.len());
+
+        for (Token { range, raw }, level) in command.tokens.iter().zip(command.levels.iter()) {
+            match CstRuleStore::new().builtins().get(raw) {
+                Some(rule) => levels.push((rule, *level)),
+                None => diagnostics.push(self.unknown_rule(raw, range)),
+            }
+        }
+        (diagnostics, levels)
+    }
+
+    fn unknown_rule(&self, raw: &str, range: &Range<usize>) -> Diagnostic {
+        let mut err = self
+            .err(format!("unknown rule `{}` used in directive", raw))
+            .primary(range.to_owned(), "");
+
+        if let Some(suggestion) = find_best_match_for_name(
+            CstRuleStore::new()
+                .builtins()
+                .rules
+                .iter()
+                .map(|x| x.name()),
+            raw,
+            None,
+        ) {
+            err = err.note(format!("help: did you mean `{}`?", suggestion));
+        }
+        err.into()
+    }
+
     /// Extract directives which apply to the whole file such as `rslint-ignore` or `rslint-ignore rule`.
     fn extract_top_level_directives(&self) -> Result<Vec<RawDirective>, Diagnostic> {
         let comments: Vec<Comment> = self
@@ -286,7 +405,11 @@ impl<'store> DirectiveParser<'store> {
                         Command::IgnoreRulesFile(vec![])
                     };
 
-                    Ok(RawCommand { tokens, kind })
+                    Ok(RawCommand {
+                        tokens,
+                        levels: vec![],
+                        kind,
+                    })
                 } else {
                     let kind = if let Some(node) = node {
                         Command::IgnoreNode(node.text_range())
@@ -296,12 +419,43 @@ impl<'store> DirectiveParser<'store> {
 
                     Ok(RawCommand {
                         tokens: vec![],
+                        levels: vec![],
                         kind,
                     })
                 }
             }
+            "level" => {
+                if node.is_some() {
+                    return Err(self
+                        .err("`level` commands can only be used for the entire file")
+                        .primary(word.range, "")
+                        .note("help: move the directive before any statement or declaration, followed by an empty line")
+                        .into());
+                }
+
+                let mut tokens = vec![];
+                let mut levels = vec![];
+                for (rule, level) in lexer.level_list()? {
+                    let parsed = level.raw.parse().map_err(|_| {
+                        self.err(format!(
+                            "invalid level `{}` for the rule `{}`",
+                            level.raw, rule.raw
+                        ))
+                        .primary(level.range.clone(), "")
+                        .note("help: the level of a rule must be `off`, `info`, `warn`, or `error`")
+                    })?;
+                    tokens.push(rule);
+                    levels.push(parsed);
+                }
+
+                Ok(RawCommand {
+                    tokens,
+                    levels,
+                    kind: Command::RuleLevels(vec![]),
+                })
+            }
             text => {
-                const COMMANDS: [&str; 1] = ["ignore"];
+                const COMMANDS: [&str; 2] = ["ignore", "level"];
 
                 let mut err = self
                     .err(format!("unknown directive command `{}`", text))
@@ -362,8 +516,9 @@ impl<'src> Lexer<'src> {
         let peeked = self.raw.peek();
         if let Some((tok, _)) = peeked {
             if tok.kind.is_trivia() {
+                self.cur += tok.len;
                 self.raw.next();
-                return self.peek();
+                return self.peek_no_whitespace();
             }
             Some(*tok)
         } else {
@@ -383,10 +538,6 @@ impl<'src> Lexer<'src> {
         self.cur - token.len..self.cur
     }
 
-    fn range_inclusive(&self, token: rslint_lexer::Token) -> Range<usize> {
-        self.cur - token.len..self.cur + 1
-    }
-
     pub fn word(&mut self) -> Result<Token, Diagnostic> {
         let end = self.src.len() + self.offset;
         let next: rslint_lexer::Token = self.next().ok_or_else(|| {
@@ -417,7 +568,7 @@ impl<'src> Lexer<'src> {
             .primary(end..end + 1, "comment ends here")
             .finish()
         })?;
-        let start = self.range(next).start + 1;
+        let start = self.range(next).start;
         let mut tok = next;
 
         loop {
@@ -428,14 +579,14 @@ impl<'src> Lexer<'src> {
                     tok = self.next().unwrap();
                     continue;
                 } else {
-                    let range = start..self.range_inclusive(tok).end;
+                    let range = start..self.range(tok).end;
                     return Ok(Token {
                         range: range.clone(),
                         raw: self.src[range].to_string(),
                     });
                 }
             } else {
-                let range = start..self.range_inclusive(tok).end;
+                let range = start..self.range(tok).end;
                 return Ok(Token {
                     range: range.clone(),
                     raw: self.src[range].to_string(),
@@ -444,6 +595,33 @@ impl<'src> Lexer<'src> {
         }
     }
 
+    /// Parse a comma separated list of rule names, each followed by a colon and a level.
+    pub fn level_list(&mut self) -> Result<Vec<(Token, Token)>, Diagnostic> {
+        let mut pairs = vec![];
+
+        loop {
+            let rule = self.rule_name()?;
+            if self.peek_no_whitespace().map(|t| t.kind) != Some(T![:]) {
+                return Err(self
+                    .err(format!("Expected a level for the rule `{}`", rule.raw))
+                    .primary(rule.range.clone(), "")
+                    .note(format!(
+                        "help: give the rule a level, e.g. `{}: warn`",
+                        rule.raw
+                    ))
+                    .into());
+            }
+            self.next();
+            pairs.push((rule, self.word()?));
+
+            if self.peek_no_whitespace().map(|t| t.kind) == Some(T![,]) {
+                self.next();
+            } else {
+                return Ok(pairs);
+            }
+        }
+    }
+
     pub fn rule_list(&mut self) -> Result<Vec<Token>, Diagnostic> {
         let mut toks = vec![];
 
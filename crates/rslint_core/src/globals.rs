@@ -0,0 +1,471 @@
+//! Globals which are known to exist in different environments, and the set of globals of a lint run.
+//!
+//! The globals of a file come from the environments and globals in the config, as well as
+//! `/* global foo */` comments inside of the file. Rules such as `no-undef` use them to tell apart
+//! references to undeclared variables and references to globals provided by the runtime.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The names of the environments which can be used with [`env_globals`].
+pub const ENVIRONMENTS: [&str; 13] = [
+    "builtin", "es6", "es2015", "es2017", "es2020", "es2021", "browser", "worker", "node",
+    "commonjs", "jest", "mocha", "jasmine",
+];
+
+/// The environments which are enabled if the config does not say otherwise.
+pub const DEFAULT_ENVIRONMENTS: [&str; 1] = ["es2021"];
+
+/// Get the globals of an environment by name, or `None` if the environment does not exist.
+/// ECMAScript environments include the globals of every earlier version.
+pub fn env_globals(env: &str) -> Option<Vec<&'static str>> {
+    let lists: &[&[&str]] = match env {
+        "builtin" => &[BUILTIN],
+        "es6" | "es2015" => &[ES2015],
+        "es2017" => &[ES2015, ES2017],
+        "es2020" => &[ES2015, ES2017, ES2020],
+        "es2021" => &[ES2015, ES2017, ES2020, ES2021],
+        "browser" => &[BROWSER],
+        "worker" => &[WORKER],
+        "node" => &[NODE, COMMONJS],
+        "commonjs" => &[COMMONJS],
+        "jest" => &[JEST],
+        "mocha" => &[MOCHA],
+        "jasmine" => &[JASMINE],
+        _ => return None,
+    };
+    Some(lists.iter().flat_map(|list| list.iter().copied()).collect())
+}
+
+/// How a global may be used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GlobalKind {
+    /// The global may be read but should not be assigned to.
+    #[serde(alias = "readable")]
+    Readonly,
+    /// The global may be read and assigned to.
+    #[serde(alias = "writeable")]
+    Writable,
+    /// The global is not available, this is used to disable a global from an environment.
+    Off,
+}
+
+/// A set of globals and how they may be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Globals {
+    globals: HashMap<String, GlobalKind>,
+}
+
+impl Default for Globals {
+    /// The ES5 builtins and the globals of the [`DEFAULT_ENVIRONMENTS`].
+    fn default() -> Self {
+        let mut globals = Self::builtin();
+        for env in DEFAULT_ENVIRONMENTS.iter() {
+            globals.add_env(env);
+        }
+        globals
+    }
+}
+
+impl Globals {
+    /// A set with only the ES5 builtins such as `Math` and `undefined`, these are always available.
+    pub fn builtin() -> Self {
+        Self {
+            globals: BUILTIN
+                .iter()
+                .map(|name| (name.to_string(), GlobalKind::Readonly))
+                .collect(),
+        }
+    }
+
+    /// Add the globals of an environment as readonly globals, returns `false` if the environment does not exist.
+    /// Globals which were already added keep their previous kind.
+    pub fn add_env(&mut self, env: &str) -> bool {
+        if let Some(names) = env_globals(env) {
+            for name in names {
+                self.globals
+                    .entry(name.to_string())
+                    .or_insert(GlobalKind::Readonly);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Declare a global, overwriting its kind if it was already declared.
+    pub fn insert(&mut self, name: impl Into<String>, kind: GlobalKind) {
+        self.globals.insert(name.into(), kind);
+    }
+
+    /// Get how a global may be used, `None` if it was never declared.
+    pub fn get(&self, name: &str) -> Option<GlobalKind> {
+        self.globals.get(name).copied()
+    }
+
+    /// Whether a global is available, meaning it is declared and it is not `off`.
+    pub fn is_declared(&self, name: &str) -> bool {
+        self.get(name).map_or(false, |kind| kind != GlobalKind::Off)
+    }
+
+    /// Iterate over every global and its kind in an unspecified order, including `off` globals.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, GlobalKind)> {
+        self.globals
+            .iter()
+            .map(|(name, kind)| (name.as_str(), *kind))
+    }
+}
+
+/// Globals defined by ES5, these are always available.
+pub const BUILTIN: &[&str] = &[
+    "Array",
+    "Boolean",
+    "Date",
+    "decodeURI",
+    "decodeURIComponent",
+    "encodeURI",
+    "encodeURIComponent",
+    "Error",
+    "escape",
+    "eval",
+    "EvalError",
+    "Function",
+    "Infinity",
+    "isFinite",
+    "isNaN",
+    "JSON",
+    "Math",
+    "NaN",
+    "Number",
+    "Object",
+    "parseFloat",
+    "parseInt",
+    "RangeError",
+    "ReferenceError",
+    "RegExp",
+    "String",
+    "SyntaxError",
+    "TypeError",
+    "undefined",
+    "unescape",
+    "URIError",
+];
+
+/// Globals added in ES2015.
+pub const ES2015: &[&str] = &[
+    "ArrayBuffer",
+    "DataView",
+    "Float32Array",
+    "Float64Array",
+    "Int16Array",
+    "Int32Array",
+    "Int8Array",
+    "Map",
+    "Promise",
+    "Proxy",
+    "Reflect",
+    "Set",
+    "Symbol",
+    "Uint16Array",
+    "Uint32Array",
+    "Uint8Array",
+    "Uint8ClampedArray",
+    "WeakMap",
+    "WeakSet",
+];
+
+/// Globals added in ES2017.
+pub const ES2017: &[&str] = &["Atomics", "SharedArrayBuffer"];
+
+/// Globals added in ES2020.
+pub const ES2020: &[&str] = &["BigInt", "BigInt64Array", "BigUint64Array", "globalThis"];
+
+/// Globals added in ES2021.
+pub const ES2021: &[&str] = &["AggregateError", "FinalizationRegistry", "WeakRef"];
+
+/// Globals provided by web browsers.
+pub const BROWSER: &[&str] = &[
+    "AbortController",
+    "AbortSignal",
+    "addEventListener",
+    "alert",
+    "atob",
+    "Audio",
+    "Blob",
+    "BroadcastChannel",
+    "btoa",
+    "caches",
+    "cancelAnimationFrame",
+    "CanvasRenderingContext2D",
+    "clearInterval",
+    "clearTimeout",
+    "close",
+    "confirm",
+    "console",
+    "crypto",
+    "CustomEvent",
+    "customElements",
+    "devicePixelRatio",
+    "dispatchEvent",
+    "document",
+    "Document",
+    "DOMParser",
+    "Element",
+    "Event",
+    "EventSource",
+    "EventTarget",
+    "fetch",
+    "File",
+    "FileList",
+    "FileReader",
+    "FormData",
+    "getComputedStyle",
+    "Headers",
+    "history",
+    "HTMLElement",
+    "HTMLInputElement",
+    "IntersectionObserver",
+    "Image",
+    "indexedDB",
+    "innerHeight",
+    "innerWidth",
+    "KeyboardEvent",
+    "localStorage",
+    "location",
+    "matchMedia",
+    "MessageChannel",
+    "MouseEvent",
+    "MutationObserver",
+    "navigator",
+    "Node",
+    "NodeList",
+    "Notification",
+    "open",
+    "performance",
+    "postMessage",
+    "prompt",
+    "queueMicrotask",
+    "removeEventListener",
+    "Request",
+    "requestAnimationFrame",
+    "requestIdleCallback",
+    "ResizeObserver",
+    "Response",
+    "screen",
+    "scroll",
+    "scrollTo",
+    "scrollX",
+    "scrollY",
+    "self",
+    "sessionStorage",
+    "setInterval",
+    "setTimeout",
+    "SharedWorker",
+    "structuredClone",
+    "TextDecoder",
+    "TextEncoder",
+    "top",
+    "URL",
+    "URLSearchParams",
+    "WebSocket",
+    "window",
+    "Worker",
+    "XMLHttpRequest",
+];
+
+/// Globals provided to web workers.
+pub const WORKER: &[&str] = &[
+    "addEventListener",
+    "atob",
+    "Blob",
+    "btoa",
+    "caches",
+    "clearInterval",
+    "clearTimeout",
+    "close",
+    "console",
+    "crypto",
+    "fetch",
+    "FileReader",
+    "FormData",
+    "Headers",
+    "importScripts",
+    "indexedDB",
+    "location",
+    "navigator",
+    "onmessage",
+    "performance",
+    "postMessage",
+    "queueMicrotask",
+    "removeEventListener",
+    "Request",
+    "Response",
+    "self",
+    "setInterval",
+    "setTimeout",
+    "TextDecoder",
+    "TextEncoder",
+    "URL",
+    "URLSearchParams",
+    "WebSocket",
+    "XMLHttpRequest",
+];
+
+/// Globals provided by Node.js.
+pub const NODE: &[&str] = &[
+    "Buffer",
+    "clearImmediate",
+    "clearInterval",
+    "clearTimeout",
+    "console",
+    "global",
+    "process",
+    "queueMicrotask",
+    "setImmediate",
+    "setInterval",
+    "setTimeout",
+    "TextDecoder",
+    "TextEncoder",
+    "URL",
+    "URLSearchParams",
+];
+
+/// Globals provided by the CommonJS module wrapper.
+pub const COMMONJS: &[&str] = &["__dirname", "__filename", "exports", "module", "require"];
+
+/// Globals provided by the Jest testing framework.
+pub const JEST: &[&str] = &[
+    "afterAll",
+    "afterEach",
+    "beforeAll",
+    "beforeEach",
+    "describe",
+    "expect",
+    "fit",
+    "it",
+    "jest",
+    "test",
+    "xdescribe",
+    "xit",
+    "xtest",
+];
+
+/// Globals provided by the Mocha testing framework.
+pub const MOCHA: &[&str] = &[
+    "after",
+    "afterEach",
+    "before",
+    "beforeEach",
+    "context",
+    "describe",
+    "it",
+    "mocha",
+    "run",
+    "setup",
+    "specify",
+    "suite",
+    "suiteSetup",
+    "suiteTeardown",
+    "teardown",
+    "test",
+    "xcontext",
+    "xdescribe",
+    "xit",
+    "xspecify",
+];
+
+/// Globals provided by the Jasmine testing framework.
+pub const JASMINE: &[&str] = &[
+    "afterAll",
+    "afterEach",
+    "beforeAll",
+    "beforeEach",
+    "describe",
+    "expect",
+    "fail",
+    "fdescribe",
+    "fit",
+    "it",
+    "jasmine",
+    "pending",
+    "spyOn",
+    "xdescribe",
+    "xit",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{groups::errors::NoUndef, lint_file, CstRuleStore};
+
+    fn undefined_names(code: &str, globals: Globals) -> Vec<String> {
+        let mut store = CstRuleStore::new();
+        store.load_rules(vec![Box::new(NoUndef::default()) as Box<dyn crate::CstRule>]);
+        store.globals = globals;
+
+        let res = lint_file(0, code, true, &store, false).unwrap();
+        assert!(res.directive_diagnostics.is_empty());
+        let mut names = res
+            .diagnostics()
+            .map(|diag| diag.message.split('`').nth(1).unwrap().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn environments() {
+        let mut globals = Globals::builtin();
+        assert!(globals.is_declared("Math"));
+        assert!(!globals.is_declared("Promise"));
+        assert!(globals.add_env("es2015"));
+        assert!(globals.is_declared("Promise"));
+        assert!(!globals.add_env("not-an-env"));
+
+        assert!(Globals::default().is_declared("WeakRef"));
+        assert!(!Globals::default().is_declared("window"));
+
+        globals.insert("Promise", GlobalKind::Off);
+        assert!(!globals.is_declared("Promise"));
+        globals.add_env("es2021");
+        assert_eq!(globals.get("Promise"), Some(GlobalKind::Off));
+    }
+
+    #[test]
+    fn config_globals() {
+        let code = "window.foo = require(jQuery);";
+        assert_eq!(
+            undefined_names(code, Globals::default()),
+            vec!["jQuery", "require", "window"]
+        );
+
+        let mut globals = Globals::default();
+        globals.add_env("browser");
+        globals.add_env("node");
+        globals.insert("jQuery", GlobalKind::Readonly);
+        globals.insert("require", GlobalKind::Off);
+        assert_eq!(undefined_names(code, globals), vec!["require"]);
+    }
+
+    #[test]
+    fn global_comments() {
+        let code = "
+            /* global foo, bar: writable, Promise: off -- from a script tag */
+            foo(bar, baz, Promise);
+            function f() {
+                /*globals baz*/
+            }
+            // global qux
+            qux;
+        ";
+        assert_eq!(
+            undefined_names(code, Globals::default()),
+            vec!["Promise", "qux"]
+        );
+
+        let store = CstRuleStore::new();
+        let res = lint_file(0, "/* global foo: maybe */", true, &store, false).unwrap();
+        assert_eq!(res.directive_diagnostics.len(), 1);
+    }
+}
@@ -35,6 +35,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     ForDirection,
     errors,
     "for-direction"
@@ -58,6 +58,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     GetterReturn,
     errors,
     "getter-return",
@@ -90,19 +91,19 @@ impl CstRule for GetterReturn {
                                 }
                                 match literal_prop.value()? {
                                     Expr::FnExpr(decl) => {
-                                        self.check_stmts(
+                                        self.check_function(
                                             args[1].syntax(),
+                                            decl.syntax(),
                                             decl.body()?.syntax(),
-                                            decl.body()?.stmts(),
                                             ctx,
                                         );
                                     }
                                     Expr::ArrowExpr(arrow) => {
                                         if let ExprOrBlock::Block(block) = arrow.body()? {
-                                            self.check_stmts(
+                                            self.check_function(
                                                 args[1].syntax(),
+                                                arrow.syntax(),
                                                 block.syntax(),
-                                                block.stmts(),
                                                 ctx,
                                             );
                                         }
@@ -118,7 +119,7 @@ impl CstRule for GetterReturn {
                 let getter = node.to::<Getter>();
                 if let Some(body) = getter.body() {
                     if let Some(key) = getter.key() {
-                        self.check_stmts(key.syntax(), body.syntax(), body.stmts(), ctx);
+                        self.check_function(key.syntax(), node, body.syntax(), ctx);
                     }
                 }
             }
@@ -129,14 +130,24 @@ impl CstRule for GetterReturn {
 }
 
 impl GetterReturn {
-    fn check_stmts(
+    fn check_function(
         &self,
         key: &SyntaxNode,
+        function: &SyntaxNode,
         body: &SyntaxNode,
-        mut stmts: impl Iterator<Item = Stmt>,
         ctx: &mut RuleCtx,
-    ) {
-        if !stmts.any(|stmt| self.check_stmt(&stmt)) {
+    ) -> Option<()> {
+        let graph = ctx.cfg.function(function)?;
+        let returns_undefined = !self.allow_implicit
+            && function
+                .descendants()
+                .filter_map(|node| node.try_to::<ReturnStmt>())
+                .any(|stmt| {
+                    stmt.value().is_none()
+                        && graph.is_statement_reachable(stmt.syntax()) == Some(true)
+                });
+
+        if graph.completes_normally() || returns_undefined {
             let err = ctx
                 .err(
                     self.name(),
@@ -153,34 +164,7 @@ impl GetterReturn {
 
             ctx.add_err(err);
         }
-    }
-
-    fn check_stmt(&self, stmt: &Stmt) -> bool {
-        match stmt {
-            Stmt::IfStmt(if_stmt) => self.check_if(if_stmt),
-            Stmt::BlockStmt(block) => block.stmts().any(|stmt| self.check_stmt(&stmt)),
-            Stmt::ReturnStmt(stmt) => stmt.value().is_some() || self.allow_implicit,
-            Stmt::SwitchStmt(switch) => switch.cases().any(|case| match case {
-                SwitchCase::CaseClause(clause) => clause.cons().any(|s| self.check_stmt(&s)),
-                SwitchCase::DefaultClause(clause) => clause.cons().any(|s| self.check_stmt(&s)),
-            }),
-            _ => false,
-        }
-    }
-
-    /// Check if an if statement unconditionally returns from the statement.
-    fn check_if(&self, stmt: &IfStmt) -> bool {
-        if stmt.alt().is_none() {
-            return false;
-        }
-
-        if let Some(cons) = stmt.cons() {
-            if !self.check_stmt(&cons) {
-                return false;
-            }
-            return self.check_stmt(&stmt.alt().unwrap());
-        }
-        false
+        None
     }
 }
 
@@ -226,6 +210,25 @@ rule_tests! {
                 }
             }
         }
+        ",
+        "
+        let bar = {
+            get foo() {
+                if (bar) {
+                    return;
+                }
+                return 5;
+            }
+        }
+        ",
+        "
+        let bar = {
+            get foo() {
+                while (bar) {
+                    return 5;
+                }
+            }
+        }
         "
     },
     ok: {
@@ -250,6 +253,29 @@ rule_tests! {
                 }
             }
         }
+        ",
+        "
+        let bar = {
+            get foo() {
+                switch (bar) {
+                    case 5:
+                        return 5;
+                    default:
+                        throw new Error();
+                }
+            }
+        }
+        ",
+        "
+        Object.defineProperty(foo, \"bar\", {
+            get: function() {
+                try {
+                    return compute();
+                } catch (e) {
+                    return null;
+                }
+            }
+        })
         "
     }
 }
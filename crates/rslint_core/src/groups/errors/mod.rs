@@ -30,4 +30,8 @@ group! {
     no_setter_return::NoSetterReturn,
     valid_typeof::ValidTypeof,
     no_extra_boolean_cast::NoExtraBooleanCast,
+    no_unreachable::NoUnreachable,
+    no_fallthrough::NoFallthrough,
+    no_unused_vars::NoUnusedVars,
+    no_undef::NoUndef,
 }
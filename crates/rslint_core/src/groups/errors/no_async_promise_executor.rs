@@ -39,6 +39,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoAsyncPromiseExecutor,
     errors,
     "no-async-promise-executor"
@@ -31,6 +31,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoCompareNegZero,
     errors,
     "no-compare-neg-zero"
@@ -35,6 +35,7 @@ declare_lint! {
     ```
     */
     #[serde(default)]
+    #[rule(recommended)]
     NoCondAssign,
     errors,
     "no-cond-assign",
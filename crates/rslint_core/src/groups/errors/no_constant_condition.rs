@@ -37,6 +37,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoConstantCondition,
     errors,
     "no-constant-condition"
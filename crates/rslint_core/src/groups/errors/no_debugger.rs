@@ -1,4 +1,5 @@
 use crate::rule_prelude::*;
+use SyntaxKind::*;
 
 declare_lint! {
     /**
@@ -18,6 +19,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended, fixable)]
     NoDebugger,
     errors,
     "no-debugger"
@@ -26,11 +28,22 @@ declare_lint! {
 #[typetag::serde]
 impl CstRule for NoDebugger {
     fn check_node(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
-        if node.kind() == SyntaxKind::DEBUGGER_STMT {
-            let err = ctx
+        if node.kind() == DEBUGGER_STMT {
+            let mut err = ctx
                 .err(self.name(), "Debugger statements are not allowed")
                 .primary(node, "");
 
+            // removing the statement is only safe if it is in a statement list, `if (foo) debugger` would be
+            // turned into invalid code otherwise.
+            if node.parent().map_or(false, |parent| {
+                matches!(
+                    parent.kind(),
+                    BLOCK_STMT | SCRIPT | MODULE | CASE_CLAUSE | DEFAULT_CLAUSE
+                )
+            }) {
+                err = err.fix(Fixer::new().delete(node));
+            }
+
             ctx.add_err(err);
         }
         None
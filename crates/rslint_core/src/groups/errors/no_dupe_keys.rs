@@ -20,6 +20,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoDupeKeys,
     errors,
     "no-dupe-keys"
@@ -34,6 +34,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoDuplicateCases,
     errors,
     "no-duplicate-cases"
@@ -31,6 +31,7 @@ declare_lint! {
     */
     #[derive(Default)]
     #[serde(default)]
+    #[rule(recommended)]
     NoEmpty,
     errors,
     "no-empty",
@@ -21,6 +21,7 @@ declare_lint! {
     */
     #[derive(Default)]
     #[serde(default)]
+    #[rule(recommended, fixable)]
     NoExtraBooleanCast,
     errors,
     "no-extra-boolean-cast",
@@ -50,29 +51,46 @@ impl CstRule for NoExtraBooleanCast {
                     .try_to::<Expr>()?;
 
                 if expr.op()? != op![!]
-                    || !matches!(child, Expr::UnaryExpr(expr) if expr.op()? == op![!])
+                    || !matches!(&child, Expr::UnaryExpr(expr) if expr.op()? == op![!])
                 {
                     return None;
                 }
 
-                if let Some(reason) = in_bool_ctx(node, self.enforce_for_logical_operands) {
-                    let err = ctx.err(self.name(), "redundant double negation").primary(
+                if let Some(reason) =
+                    in_bool_ctx(node, self.enforce_for_logical_operands, ctx.scope)
+                {
+                    let mut err = ctx.err(self.name(), "redundant double negation").primary(
                         expr.op_token().unwrap().text_range(),
                         "this operator is redundant...",
                     );
+
+                    // the operand of a unary expression always binds tighter than the unary expression
+                    // so we can take its text as is.
+                    if let Some(operand) = child.syntax().to::<UnaryExpr>().expr() {
+                        if !node.contains_comments() {
+                            let text = operand.syntax().trimmed_text().to_string();
+                            err = err.fix(Fixer::new().replace(node, text));
+                        }
+                    }
                     ctx.add_err(reason_labels(err, reason));
                 }
             }
             CALL_EXPR => {
-                if !util::constructor_or_call_with_callee(node, "Boolean") {
+                if !calls_global_boolean(node, ctx.scope) {
                     return None;
                 }
 
-                if let Some(reason) = in_bool_ctx(node, self.enforce_for_logical_operands) {
-                    let err = ctx.err(self.name(), "redundant `Boolean` call").primary(
+                if let Some(reason) =
+                    in_bool_ctx(node, self.enforce_for_logical_operands, ctx.scope)
+                {
+                    let mut err = ctx.err(self.name(), "redundant `Boolean` call").primary(
                         node.trimmed_range(),
                         "this call to `Boolean` is redundant...",
                     );
+
+                    if let Some(replacement) = boolean_call_replacement(node) {
+                        err = err.fix(Fixer::new().replace(node, replacement));
+                    }
                     ctx.add_err(reason_labels(err, reason));
                 }
             }
@@ -82,6 +100,31 @@ impl CstRule for NoExtraBooleanCast {
     }
 }
 
+/// The text to replace a redundant `Boolean(foo)` call with, calls without exactly one
+/// argument are not fixed because their value is not simply the argument.
+fn boolean_call_replacement(node: &SyntaxNode) -> Option<std::string::String> {
+    let args = node.to::<CallExpr>().arguments()?;
+    if node.contains_comments() || args.syntax().children().count() != 1 {
+        return None;
+    }
+    let arg = args.args().next()?;
+    let text = arg.syntax().trimmed_text().to_string();
+
+    Some(match arg {
+        Expr::NameRef(_)
+        | Expr::Literal(_)
+        | Expr::ThisExpr(_)
+        | Expr::ArrayExpr(_)
+        | Expr::GroupingExpr(_)
+        | Expr::BracketExpr(_)
+        | Expr::DotExpr(_)
+        | Expr::CallExpr(_)
+        | Expr::NewExpr(_)
+        | Expr::Template(_) => text,
+        _ => format!("({})", text),
+    })
+}
+
 fn reason_labels(builder: DiagnosticBuilder, reason: Reason) -> DiagnosticBuilder {
     match reason {
         Reason::ExplicitBoolean(node) => builder.secondary(
@@ -99,12 +142,21 @@ fn reason_labels(builder: DiagnosticBuilder, reason: Reason) -> DiagnosticBuilde
     }
 }
 
-fn in_bool_ctx(node: &SyntaxNode, enforce_logical: bool) -> Option<Reason> {
+/// Whether a call or new expression calls the global `Boolean` and not a binding which shadows it.
+fn calls_global_boolean(node: &SyntaxNode, scope: &ScopeAnalysis) -> bool {
+    util::constructor_or_call_with_callee(node, "Boolean")
+        && node.child_with_kind(NAME_REF).map_or(false, |callee| {
+            scope
+                .reference_at(callee)
+                .map_or(true, |reference| reference.binding.is_none())
+        })
+}
+
+fn in_bool_ctx(node: &SyntaxNode, enforce_logical: bool, scope: &ScopeAnalysis) -> Option<Reason> {
     let parent = skip_grouping(node.parent(), SyntaxNode::parent).nth(1);
     if let Some(parent) = parent {
-        // TODO: Once we have scope analysis we can know if Boolean was shadowed
         // new Boolean(foo) or Boolean(foo)
-        if util::constructor_or_call_with_callee(parent.clone(), "Boolean") {
+        if calls_global_boolean(&parent, scope) {
             return parent
                 .child_with_kind(ARG_LIST)
                 .filter(|cond| {
@@ -158,7 +210,7 @@ fn in_bool_ctx(node: &SyntaxNode, enforce_logical: bool) -> Option<Reason> {
                 op if op == op![||] || op == op![&&] => Some(()),
                 _ => None,
             })
-            .and_then(|_| in_bool_ctx(expr.syntax(), true))
+            .and_then(|_| in_bool_ctx(expr.syntax(), true, scope))
     } else {
         None
     }
@@ -422,7 +474,9 @@ rule_tests! {
         "var foo = bar() ? Boolean(baz) : Boolean(bat)",
         "for(Boolean(foo);;) {}",
         "for(;; Boolean(foo)) {}",
-        "if (new Boolean(foo)) {}"
+        "if (new Boolean(foo)) {}",
+        "function foo(Boolean) { if (Boolean(bar)) {} }",
+        "const Boolean = x => x; var foo = Boolean(!!bar);"
     }
 }
 
@@ -23,6 +23,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended, fixable)]
     NoExtraSemi,
     errors,
     "no-extra-semi"
@@ -49,7 +50,8 @@ impl CstRule for NoExtraSemi {
         {
             let err = ctx
                 .err(self.name(), "Unnecessary semicolon")
-                .primary(node, "help: delete this semicolon");
+                .primary(node, "help: delete this semicolon")
+                .fix(Fixer::new().delete(node));
 
             ctx.add_err(err);
         }
@@ -0,0 +1,233 @@
+use crate::rule_prelude::*;
+use SyntaxKind::*;
+
+declare_lint! {
+    /**
+    Disallow `case` clauses in switch statements which fall through to the next case.
+
+    Unless a `case` ends with a statement like `break` or `return`, the statements of the next
+    case are run as well. This is easy to forget and often results in subtle bugs.
+
+    Falling through on purpose is allowed if the last comment of the case matches `falls through`
+    (case insensitively, `fall through`, `fallthrough`, and `falls through` are all accepted).
+    Empty cases such as `case 1: case 2:` are also allowed.
+
+    ## Invalid Code Examples
+
+    ```js
+    switch (action.type) {
+        case "add":
+            state.count += 1;
+        case "remove":
+            state.count -= 1;
+    }
+    ```
+
+    ## Correct Code Examples
+
+    ```js
+    switch (action.type) {
+        case "add":
+            state.count += 1;
+            break;
+        case "reset":
+            state.count = 0;
+            // falls through
+        case "remove":
+        case "delete":
+            state.count -= 1;
+    }
+    ```
+    */
+    #[derive(Default)]
+    #[rule(recommended)]
+    NoFallthrough,
+    errors,
+    "no-fallthrough"
+}
+
+#[typetag::serde]
+impl CstRule for NoFallthrough {
+    fn check_node(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+        if !matches!(node.kind(), CASE_CLAUSE | DEFAULT_CLAUSE) {
+            return None;
+        }
+
+        let next = std::iter::successors(node.next_sibling(), |sibling| sibling.next_sibling())
+            .find(|sibling| matches!(sibling.kind(), CASE_CLAUSE | DEFAULT_CLAUSE))?;
+
+        let is_empty = !node.children().any(|child| child.is::<ast::StmtListItem>());
+        if is_empty || !ctx.cfg.graph_containing(node).case_end_reachable(node)? {
+            return None;
+        }
+
+        let end = next.text_range().start();
+        let has_comment = node
+            .parent()?
+            .descendants_with_tokens()
+            .filter_map(|elem| elem.into_token())
+            .filter(|tok| {
+                tok.kind() == COMMENT
+                    && tok.text_range().start() >= node.text_range().start()
+                    && tok.text_range().end() <= end
+            })
+            .last()
+            .map_or(false, |comment| is_fallthrough_comment(comment.text()));
+
+        if has_comment {
+            return None;
+        }
+
+        let err = ctx
+            .err(self.name(), "this case falls through to the next case")
+            .primary(clause_head(node), "the statements of this case can finish...")
+            .secondary(
+                clause_head(&next),
+                "...and then the statements of this case will run too",
+            )
+            .note("help: add a `break` if this is a mistake, or a `// falls through` comment if it is on purpose");
+
+        ctx.add_err(err);
+        None
+    }
+}
+
+/// The range of `case foo:` or `default:` in a clause.
+fn clause_head(clause: &SyntaxNode) -> std::ops::Range<usize> {
+    let start = clause.trimmed_range().start();
+    let end = clause
+        .children_with_tokens()
+        .find(|elem| elem.kind() == T![:])
+        .map_or(clause.trimmed_range().end(), |colon| {
+            colon.text_range().end()
+        });
+    start.into()..end.into()
+}
+
+/// Whether a comment matches `falls?\s?through` case insensitively.
+fn is_fallthrough_comment(comment: &str) -> bool {
+    let lower = comment.to_ascii_lowercase();
+    lower.match_indices("fall").any(|(idx, _)| {
+        let mut rest = &lower[idx + 4..];
+        rest = rest.strip_prefix('s').unwrap_or(rest);
+        if let Some(ch) = rest.chars().next().filter(|c| c.is_whitespace()) {
+            rest = &rest[ch.len_utf8()..];
+        }
+        rest.starts_with("through")
+    })
+}
+
+rule_tests! {
+    NoFallthrough::default(),
+    err: {
+        "
+        switch (foo) {
+            case 1:
+                bar();
+            case 2:
+                baz();
+        }
+        ",
+        "
+        switch (foo) {
+            case 1:
+                if (bar) {
+                    break;
+                }
+            default:
+                baz();
+        }
+        ",
+        "
+        switch (foo) {
+            default:
+                bar();
+            case 1:
+                baz();
+        }
+        ",
+        "
+        switch (foo) {
+            case 1:
+                bar();
+                // this is fine
+            case 2:
+        }
+        ",
+        "
+        function foo(bar) {
+            switch (bar) {
+                case 1:
+                    try {
+                        return 1;
+                    } catch (e) {
+                        log(e);
+                    }
+                case 2:
+                    return 2;
+            }
+        }
+        "
+    },
+    ok: {
+        "
+        switch (foo) {
+            case 1:
+                bar();
+                break;
+            case 2:
+                baz();
+        }
+        ",
+        "
+        switch (foo) {
+            case 1:
+            case 2:
+                bar();
+        }
+        ",
+        "
+        switch (foo) {
+            case 1:
+                bar();
+                // falls through
+            case 2:
+                baz();
+            /* fallthrough */
+            case 3:
+                bar(); // fall through
+            default:
+                baz();
+        }
+        ",
+        "
+        function foo(bar) {
+            switch (bar) {
+                case 1:
+                    return 1;
+                case 2:
+                    throw new Error();
+                case 3: {
+                    if (bar) {
+                        return 3;
+                    } else {
+                        return 4;
+                    }
+                }
+                default:
+                    return 5;
+            }
+        }
+        ",
+        "
+        while (foo) {
+            switch (foo) {
+                case 1:
+                    continue;
+                case 2:
+                    bar();
+            }
+        }
+        "
+    }
+}
@@ -36,6 +36,7 @@ declare_lint! {
     ```
     */
     #[serde(default)]
+    #[rule(recommended)]
     NoInnerDeclarations,
     errors,
     "no-inner-declarations",
@@ -45,6 +45,7 @@ declare_lint! {
     ```
     */
     #[serde(default)]
+    #[rule(recommended)]
     NoIrregularWhitespace,
     errors,
     "no-irregular-whitespace",
@@ -34,6 +34,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended, suggestions)]
     NoPrototypeBuiltins,
     errors,
     "no-prototype-builtins"
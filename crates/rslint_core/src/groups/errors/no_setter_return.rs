@@ -97,6 +97,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoSetterReturn,
     errors,
     "no-setter-return",
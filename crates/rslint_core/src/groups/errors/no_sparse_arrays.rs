@@ -22,6 +22,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoSparseArrays,
     errors,
     "no-sparse-arrays"
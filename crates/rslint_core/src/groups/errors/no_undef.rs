@@ -0,0 +1,140 @@
+use crate::rule_prelude::*;
+use ast::{UnaryExpr, UnaryOp};
+use SyntaxKind::*;
+
+declare_lint! {
+    /**
+    Disallow the use of undeclared variables.
+
+    Using a variable which is not declared anywhere is usually caused by a typo in the name
+    of a variable, or by a missing import. Reading an undeclared variable throws a `ReferenceError`
+    at runtime, and assigning to it creates a global variable in sloppy mode.
+
+    Globals provided by the runtime, such as `window` in browsers or `require` in node, must be declared
+    using the `[env]` and `[globals]` sections of the config, or with a `/* global foo */` comment in the file.
+    ES5 builtins such as `Math` are always allowed.
+    Undeclared variables used as the operand of `typeof` are not reported, since it is commonly used
+    to check if a global exists.
+
+    ## Invalid Code Examples
+
+    ```js
+    const result = compute(5);
+    ```
+
+    ```js
+    function foo() {
+        valeu = 5;
+    }
+    ```
+
+    ## Correct Code Examples
+
+    ```js
+    import { compute } from "./compute.js";
+
+    const result = compute(5);
+    ```
+
+    ```js
+    /* global jQuery */
+    const isBrowser = typeof window !== "undefined";
+
+    jQuery(document).ready();
+    ```
+    */
+    #[derive(Default)]
+    #[rule(recommended)]
+    NoUndef,
+    errors,
+    "no-undef"
+}
+
+#[typetag::serde]
+impl CstRule for NoUndef {
+    fn check_root(&self, root: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+        let scope = ctx.scope;
+        for reference in scope.undeclared() {
+            if is_typeof_operand(root, reference.range) {
+                continue;
+            }
+
+            let err = ctx
+                .err(self.name(), format!("`{}` is not defined", reference.name))
+                .primary(
+                    reference.range,
+                    "this name is not declared in this file, and it is not a known global",
+                );
+
+            ctx.add_err(err);
+        }
+        None
+    }
+}
+
+fn is_typeof_operand(root: &SyntaxNode, range: rslint_parser::TextRange) -> bool {
+    root.covering_element(range)
+        .into_token()
+        .and_then(|token| {
+            token
+                .parent()
+                .ancestors()
+                .find(|node| !matches!(node.kind(), NAME_REF | GROUPING_EXPR))
+                .and_then(|node| node.try_to::<UnaryExpr>())
+        })
+        .and_then(|expr| expr.op())
+        .map_or(false, |op| op == UnaryOp::Typeof)
+}
+
+rule_tests! {
+    NoUndef::default(),
+    err: {
+        "foo();",
+        "
+        function foo() {
+            valeu = 5;
+        }
+        ",
+        "
+        {
+            let foo = 5;
+        }
+        foo;
+        ",
+        "
+        function foo() {
+            return window.location;
+        }
+        ",
+        "export { foo };",
+        "
+        class Foo extends Bar {}
+        "
+    },
+    ok: {
+        "
+        import foo from \"foo\";
+        foo();
+        ",
+        "
+        bar();
+        function bar() {
+            return new Promise(resolve => resolve(Math.max(NaN, Infinity, undefined)));
+        }
+        ",
+        "
+        const isBrowser = typeof window !== \"undefined\";
+        ",
+        "typeof (foo) === \"string\"",
+        "
+        function foo() {
+            return arguments.length;
+        }
+        ",
+        "
+        try {} catch (err) {
+            err.message;
+        }
+        "
+    }
+}
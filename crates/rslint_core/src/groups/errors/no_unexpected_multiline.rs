@@ -62,6 +62,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoUnexpectedMultiline,
     errors,
     "no-unexpected-multiline"
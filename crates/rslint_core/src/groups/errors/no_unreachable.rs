@@ -0,0 +1,218 @@
+use crate::rule_prelude::*;
+use ast::VarDecl;
+use SyntaxKind::*;
+
+declare_lint! {
+    /**
+    Disallow unreachable code after `return`, `throw`, `break`, and `continue` statements.
+
+    Statements after a statement which always jumps out of the current block, such as `return`
+    or `throw`, can never be run. This is almost always a mistake, such as a `return` left in after debugging,
+    or a missing condition.
+
+    Function declarations and `var` declarations without an initializer are allowed, because they are hoisted
+    and can still be used even if they come after a `return`.
+
+    ## Invalid Code Examples
+
+    ```js
+    function foo() {
+        return 5;
+        console.log("returned 5");
+    }
+    ```
+
+    ```js
+    for (const item of items) {
+        if (item.done) {
+            continue;
+        } else {
+            break;
+        }
+        process(item);
+    }
+    ```
+    */
+    #[derive(Default)]
+    #[rule(recommended)]
+    NoUnreachable,
+    errors,
+    "no-unreachable"
+}
+
+#[typetag::serde]
+impl CstRule for NoUnreachable {
+    fn check_node(&self, node: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+        if !node.is::<ast::StmtListItem>()
+            || is_allowed(node)
+            || ctx.cfg.is_statement_reachable(node)?
+        {
+            return None;
+        }
+
+        // only report the first statement of a run of unreachable statements, statements which come first
+        // in an unreachable block are part of an unreachable statement which is already reported.
+        let terminator = statement_siblings(node, SyntaxNode::prev_sibling).next()?;
+        if !ctx.cfg.is_statement_reachable(&terminator)? {
+            return None;
+        }
+
+        let last = statement_siblings(node, SyntaxNode::next_sibling)
+            .take_while(|stmt| ctx.cfg.is_statement_reachable(stmt) == Some(false))
+            .last()
+            .unwrap_or_else(|| node.clone());
+        let start = node.trimmed_range().start();
+        let end = last.trimmed_range().end();
+
+        let label = match terminator.kind() {
+            RETURN_STMT => "...because this statement always returns",
+            THROW_STMT => "...because this statement always throws",
+            BREAK_STMT => "...because this statement always breaks out of the loop or switch",
+            CONTINUE_STMT => "...because this statement always continues the loop",
+            _ => "...because this statement never finishes normally",
+        };
+
+        let err = ctx
+            .err(self.name(), "unreachable code")
+            .primary(start..end, "this code will never run...")
+            .secondary(&terminator, label);
+
+        ctx.add_err(err);
+        None
+    }
+}
+
+/// Declarations which are hoisted and can be used even if they are after a `return`, empty statements are
+/// also allowed because they are reported by `no-extra-semi`.
+fn is_allowed(node: &SyntaxNode) -> bool {
+    match node.kind() {
+        FN_DECL | EMPTY_STMT => true,
+        VAR_DECL => {
+            let decl = node.to::<VarDecl>();
+            decl.is_var()
+                && decl
+                    .declared()
+                    .all(|declarator| declarator.value().is_none())
+        }
+        _ => false,
+    }
+}
+
+/// The sibling statements of a node in one direction, skipping over allowed statements.
+fn statement_siblings(
+    node: &SyntaxNode,
+    successor: fn(&SyntaxNode) -> Option<SyntaxNode>,
+) -> impl Iterator<Item = SyntaxNode> {
+    std::iter::successors(successor(node), successor)
+        .take_while(|sibling| sibling.is::<ast::StmtListItem>())
+        .filter(|sibling| !is_allowed(sibling))
+}
+
+rule_tests! {
+    NoUnreachable::default(),
+    err: {
+        "
+        function foo() {
+            return 5;
+            console.log(\"returned 5\");
+        }
+        ",
+        "
+        for (const item of items) {
+            if (item.done) {
+                continue;
+            } else {
+                break;
+            }
+            process(item);
+        }
+        ",
+        "
+        function foo() {
+            throw new Error();
+            function bar() {}
+            bar();
+        }
+        ",
+        "
+        while (foo) {
+            break;
+            let bar = 5;
+        }
+        ",
+        "
+        function foo(bar) {
+            switch (bar) {
+                case 1:
+                    return;
+                    bar();
+            }
+        }
+        ",
+        "
+        function foo() {
+            try {
+                return 1;
+            } finally {
+                cleanup();
+            }
+            done();
+        }
+        ",
+        "
+        while (true) {}
+        foo();
+        "
+    },
+    ok: {
+        "
+        do {
+            a();
+        } while (x);
+        try {
+            b();
+        } finally {
+            c();
+        }
+        d();
+        ",
+        "
+        function foo() {
+            return bar();
+            function bar() {}
+        }
+        ",
+        "
+        function foo() {
+            return bar;
+            var bar;
+        }
+        ",
+        "
+        function foo() {
+            if (bar) {
+                return 5;
+            }
+            baz();
+        }
+        ",
+        "
+        while (foo) {
+            if (bar) {
+                break;
+            }
+            baz();
+        }
+        ",
+        "
+        try {
+            foo();
+        } catch (e) {
+            throw e;
+        } finally {
+            bar();
+        }
+        baz();
+        "
+    }
+}
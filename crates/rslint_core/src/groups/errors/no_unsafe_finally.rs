@@ -44,6 +44,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoUnsafeFinally,
     errors,
     "no-unsafe-finally"
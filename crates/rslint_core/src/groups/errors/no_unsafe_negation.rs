@@ -27,6 +27,7 @@ declare_lint! {
     ```
     */
     #[derive(Default)]
+    #[rule(recommended)]
     NoUnsafeNegation,
     errors,
     "no-unsafe-negation"
@@ -0,0 +1,365 @@
+use crate::rule_prelude::*;
+use crate::scope::{Binding, BindingKind};
+use crate::util::Pattern;
+use ast::Declarator;
+use SyntaxKind::*;
+
+declare_lint! {
+    /**
+    Disallow variables, functions, classes, imports, and parameters which are declared but never used.
+
+    A binding which is never read is most likely left over from refactoring, or is a sign of a
+    typo or a bug somewhere else. A binding which is only ever assigned to is also considered unused.
+
+    Bindings which are exported are always considered used. By default, parameters before the last
+    used parameter and the parameters of catch clauses are not reported, since they cannot be removed.
+
+    ## Invalid Code Examples
+
+    ```js
+    import { readFile } from "fs";
+
+    function add(a, b, c) {
+        let result = a + b;
+        result = 5;
+        return a + b;
+    }
+
+    add(1, 2, 3);
+    ```
+
+    ## Correct Code Examples
+
+    ```js
+    import { readFile } from "fs";
+
+    export function read(_path, callback) {
+        readFile("foo.txt", callback);
+    }
+
+    try {
+        read();
+    } catch (e) {}
+    ```
+    */
+    #[serde(default)]
+    #[rule(recommended)]
+    NoUnusedVars,
+    errors,
+    "no-unused-vars",
+    /// Which variables to check, `"all"` (the default) checks every variable, and `"local"`
+    /// does not check variables declared at the top level of scripts, since they may be used by other scripts.
+    pub vars: Vars,
+    /// Which parameters to check, `"after-used"` (the default) only checks the parameters after the last
+    /// used parameter, `"all"` checks every parameter, and `"none"` does not check parameters.
+    pub args: Args,
+    /// Whether to check the parameters of catch clauses, `"none"` (the default) does not check them
+    /// and `"all"` checks them.
+    pub caught_errors: CaughtErrors,
+    /// A regular expression, any binding whose name matches it is not checked. For example, `"^_"` allows
+    /// any name starting with an underscore.
+    pub ignore_pattern: Option<Pattern>
+}
+
+/// Which variables `no-unused-vars` checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Vars {
+    All,
+    Local,
+}
+
+impl ConfigValues for Vars {
+    const NAMES: &'static [&'static str] = &["all", "local"];
+}
+
+/// Which parameters `no-unused-vars` checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Args {
+    AfterUsed,
+    All,
+    None,
+}
+
+impl ConfigValues for Args {
+    const NAMES: &'static [&'static str] = &["after-used", "all", "none"];
+}
+
+/// Whether `no-unused-vars` checks the parameters of catch clauses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CaughtErrors {
+    All,
+    None,
+}
+
+impl ConfigValues for CaughtErrors {
+    const NAMES: &'static [&'static str] = &["all", "none"];
+}
+
+impl Default for NoUnusedVars {
+    fn default() -> Self {
+        Self {
+            vars: Vars::All,
+            args: Args::AfterUsed,
+            caught_errors: CaughtErrors::None,
+            ignore_pattern: None,
+        }
+    }
+}
+
+#[typetag::serde]
+impl CstRule for NoUnusedVars {
+    fn check_root(&self, root: &SyntaxNode, ctx: &mut RuleCtx) -> Option<()> {
+        let scope = ctx.scope;
+        for (_, binding) in scope.bindings() {
+            if binding.is_implicit()
+                || is_used(scope, binding)
+                || self
+                    .ignore_pattern
+                    .as_ref()
+                    .map_or(false, |pattern| pattern.is_match(&binding.name))
+                || !self.should_check(scope, binding)
+            {
+                continue;
+            }
+
+            let declaration = declaration_node(root, binding);
+            if declaration.as_ref().map_or(false, |node| {
+                matches!(node.kind(), FN_EXPR | CLASS_EXPR) || is_exported(node)
+            }) {
+                continue;
+            }
+
+            let assigned = binding
+                .references
+                .iter()
+                .any(|id| scope.reference(*id).is_write())
+                || declaration.map_or(false, |node| {
+                    node.try_to::<Declarator>()
+                        .map_or(false, |declarator| declarator.value().is_some())
+                });
+
+            let message = if binding.kind == BindingKind::Import {
+                format!("`{}` is imported but never used", binding.name)
+            } else if assigned {
+                format!("`{}` is assigned a value but never used", binding.name)
+            } else {
+                format!("`{}` is declared but never used", binding.name)
+            };
+
+            let err = ctx
+                .err(self.name(), message)
+                .primary(binding.range, "this binding is never read");
+
+            ctx.add_err(err);
+        }
+        None
+    }
+}
+
+impl NoUnusedVars {
+    fn should_check(&self, scope: &ScopeAnalysis, binding: &Binding) -> bool {
+        match binding.kind {
+            BindingKind::Param => match self.args {
+                Args::None => false,
+                Args::All => true,
+                Args::AfterUsed => !later_param_used(scope, binding),
+            },
+            BindingKind::CatchParam => self.caught_errors == CaughtErrors::All,
+            _ => {
+                self.vars != Vars::Local
+                    || binding.scope != scope.root()
+                    || scope.scope(binding.scope).kind != crate::scope::ScopeKind::Global
+            }
+        }
+    }
+}
+
+/// Whether a binding is read anywhere, ignoring recursive references inside of its own function or class.
+fn is_used(scope: &ScopeAnalysis, binding: &Binding) -> bool {
+    binding.references.iter().any(|id| {
+        let reference = scope.reference(*id);
+        let recursive = matches!(binding.kind, BindingKind::Function | BindingKind::Class)
+            && binding.declaration.contains_range(reference.range);
+        reference.is_read() && !recursive
+    })
+}
+
+/// Whether any parameter after this parameter in the same function is used.
+fn later_param_used(scope: &ScopeAnalysis, binding: &Binding) -> bool {
+    scope
+        .scope(binding.scope)
+        .bindings
+        .iter()
+        .map(|id| scope.binding(*id))
+        .filter(|param| {
+            param.kind == BindingKind::Param && param.range.start() > binding.range.start()
+        })
+        .any(|param| is_used(scope, param))
+}
+
+/// The node which declared a binding, e.g. a declarator, a function, or a class.
+fn declaration_node(root: &SyntaxNode, binding: &Binding) -> Option<SyntaxNode> {
+    root.covering_element(binding.range)
+        .into_token()?
+        .parent()
+        .ancestors()
+        .find(|node| node.trimmed_range() == binding.declaration)
+}
+
+fn is_exported(declaration: &SyntaxNode) -> bool {
+    declaration
+        .ancestors()
+        .skip(1)
+        .find(|node| node.kind() != VAR_DECL)
+        .map_or(false, |node| {
+            matches!(node.kind(), EXPORT_DECL | EXPORT_DEFAULT_DECL)
+        })
+}
+
+rule_tests! {
+    NoUnusedVars::default(),
+    err: {
+        "let foo = 5;",
+        "
+        let foo;
+        foo = 5;
+        ",
+        "import { readFile } from \"fs\";",
+        "function foo() {}",
+        "
+        function foo() {
+            foo();
+        }
+        ",
+        "
+        function foo(a, b, c) {
+            return b;
+        }
+        foo();
+        ",
+        "
+        const { a, b } = foo();
+        console.log(a);
+        ",
+        "
+        class Foo {
+            bar() {
+                return new Foo();
+            }
+        }
+        ",
+        "
+        export function foo() {
+            const bar = 5;
+        }
+        "
+    },
+    ok: {
+        "
+        let foo = 5;
+        console.log(foo);
+        ",
+        "
+        export const foo = 5;
+        export function bar(a) {
+            return a;
+        }
+        export default class Baz {}
+        ",
+        "
+        const foo = 5;
+        export { foo };
+        ",
+        "
+        const foo = function bar() {};
+        foo();
+        ",
+        "
+        function foo(a, b) {
+            return b;
+        }
+        foo();
+        ",
+        "
+        try {
+            foo();
+        } catch (e) {}
+        ",
+        "
+        let count = 0;
+        export function increment() {
+            count += 1;
+        }
+        ",
+        "
+        const items = [];
+        for (const item of items) {
+            console.log(item);
+        }
+        "
+    }
+}
+
+rule_tests! {
+    configured_ok,
+    configured_err,
+    NoUnusedVars {
+        vars: Vars::Local,
+        args: Args::All,
+        caught_errors: CaughtErrors::All,
+        ignore_pattern: Pattern::new("^_").ok(),
+    },
+    err: {
+        "
+        function foo(a, b) {
+            return b;
+        }
+        foo();
+        ",
+        "
+        try {
+            foo();
+        } catch (e) {}
+        "
+    },
+    ok: {
+        "
+        function foo(_a, b) {
+            return b;
+        }
+        foo();
+        ",
+        "const _unused = 5;",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::{value::Error, DeserializeOwned, IntoDeserializer};
+
+    fn parse_names<T: ConfigValues + DeserializeOwned>() -> Vec<T> {
+        T::NAMES
+            .iter()
+            .map(|name| {
+                T::deserialize(IntoDeserializer::<Error>::into_deserializer(*name)).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn config_values_are_every_variant() {
+        assert_eq!(parse_names::<Vars>(), vec![Vars::All, Vars::Local]);
+        assert_eq!(
+            parse_names::<Args>(),
+            vec![Args::AfterUsed, Args::All, Args::None]
+        );
+        assert_eq!(
+            parse_names::<CaughtErrors>(),
+            vec![CaughtErrors::All, CaughtErrors::None]
+        );
+    }
+}
@@ -38,6 +38,7 @@ declare_lint! {
     ```
     */
     #[serde(default)]
+    #[rule(recommended, suggestions)]
     UseIsnan,
     errors,
     "use-isnan",
@@ -107,16 +108,20 @@ impl CstRule for UseIsnan {
                 .note("note: `NaN` is not equal to anything including itself");
 
                 // telling the user to use isNaN for `<`, `>`, etc is a bit misleading so we won't do it if that is the case
-                if op == op!(==) || op == op!(===) {
-                    err = err.note(format!(
-                        "help: use `isNaN` instead: `{}`",
-                        color(&format!("isNaN({})", opposite))
-                    ))
+                let replacement = if op == op!(==) || op == op!(===) {
+                    Some(format!("isNaN({})", opposite.syntax().trimmed_text()))
                 } else if op == op!(!=) || op == op!(!==) {
+                    Some(format!("!isNaN({})", opposite.syntax().trimmed_text()))
+                } else {
+                    None
+                };
+
+                // this is not a fix, `isNaN` coerces its argument so it changes what the comparison does
+                if let Some(replacement) = replacement {
                     err = err.note(format!(
                         "help: use `isNaN` instead: `{}`",
-                        color(&format!("!isNaN({})", opposite))
-                    ))
+                        color(&replacement)
+                    ));
                 }
 
                 ctx.add_err(err);
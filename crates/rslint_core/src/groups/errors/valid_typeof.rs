@@ -21,6 +21,7 @@ declare_lint! {
     */
     #[derive(Default)]
     #[serde(default)]
+    #[rule(recommended, suggestions)]
     ValidTypeof,
     errors,
     "valid-typeof",
@@ -3,20 +3,30 @@ mod rule;
 mod store;
 mod testing;
 
+pub mod autofix;
+pub mod cfg;
 pub mod directives;
+pub mod globals;
 pub mod groups;
 pub mod rule_prelude;
+pub mod scope;
 pub mod util;
 
 pub use self::{
+    autofix::{fix_file, FixResult, Fixer},
+    cfg::ControlFlow,
     diagnostic::{DiagnosticBuilder, Span},
-    rule::{CstRule, Outcome, Rule, RuleCtx, RuleLevel, RuleResult},
+    rule::{
+        ConfigField, ConfigValues, CstRule, Outcome, Rule, RuleCtx, RuleLevel, RuleMeta, RuleResult,
+    },
+    scope::ScopeAnalysis,
     store::CstRuleStore,
 };
 pub use codespan_reporting::diagnostic::{Label, Severity};
 
 use crate::directives::skip_node;
 pub use crate::directives::{apply_top_level_directives, Directive, DirectiveParser};
+use codespan_reporting::diagnostic::LabelStyle;
 use dyn_clone::clone_box;
 use rayon::prelude::*;
 use rslint_parser::{parse_module, parse_text, util::SyntaxNodeExt, SyntaxKind, SyntaxNode};
@@ -28,12 +38,14 @@ pub type Diagnostic = codespan_reporting::diagnostic::Diagnostic<usize>;
 /// The result of linting a file.
 #[derive(Debug)]
 pub struct LintResult<'s> {
+    /// The id of the file which was linted
+    pub file_id: usize,
     /// Any diagnostics (errors, warnings, etc) emitted from the parser
     pub parser_diagnostics: Vec<Diagnostic>,
     /// The store used for the lint run
     pub store: &'s CstRuleStore,
-    /// The diagnostics emitted by each rule run
-    pub rule_diagnostics: HashMap<&'static str, Vec<Diagnostic>>,
+    /// The diagnostics and fixes emitted by each rule run
+    pub rule_results: HashMap<&'static str, RuleResult>,
     /// Any warnings or errors emitted by the directive parser
     pub directive_diagnostics: Vec<Diagnostic>,
 }
@@ -44,10 +56,19 @@ impl LintResult<'_> {
     pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
         self.parser_diagnostics
             .iter()
-            .chain(self.rule_diagnostics.values().map(|x| x.iter()).flatten())
+            .chain(
+                self.rule_results
+                    .values()
+                    .flat_map(|x| x.diagnostics.iter()),
+            )
             .chain(self.directive_diagnostics.iter())
     }
 
+    /// Get all of the fixes offered by rules during linting.
+    pub fn fixes(&self) -> impl Iterator<Item = &Fixer> {
+        self.rule_results.values().flat_map(|x| x.fixes.iter())
+    }
+
     /// The overall outcome of linting this file (failure, warning, success, etc)
     pub fn outcome(&self) -> Outcome {
         self.diagnostics().into()
@@ -90,23 +111,28 @@ pub fn lint_file(
         file_id,
     );
 
-    let rule_diagnostics = new_store
+    // scope analysis and control flow graphs are shared by every rule, so they are only computed once
+    let root = SyntaxNode::new_root(green.clone());
+    let scope = ScopeAnalysis::new(&root).with_globals(new_store.globals.clone());
+    let cfg = ControlFlow::new(&root);
+
+    let rule_results = new_store
         .rules
         .par_iter()
         .map(|rule| {
             let root = SyntaxNode::new_root(green.clone());
+            let mut result = run_rule(&**rule, file_id, root, verbose, &directives, &scope, &cfg);
+            remap_diagnostics_to_level(&mut result.diagnostics, new_store.level(rule.name()));
 
-            (
-                rule.name(),
-                run_rule(&**rule, file_id, root, verbose, &directives),
-            )
+            (rule.name(), result)
         })
         .collect();
 
     Ok(LintResult {
+        file_id,
         parser_diagnostics,
         store,
-        rule_diagnostics,
+        rule_results,
         directive_diagnostics,
     })
 }
@@ -121,20 +147,31 @@ pub fn run_rule(
     root: SyntaxNode,
     verbose: bool,
     directives: &[Directive],
-) -> Vec<Diagnostic> {
+    scope: &ScopeAnalysis,
+    cfg: &ControlFlow,
+) -> RuleResult {
     assert!(root.kind() == SyntaxKind::SCRIPT || root.kind() == SyntaxKind::MODULE);
     let mut ctx = RuleCtx {
         file_id,
         verbose,
+        scope,
+        cfg,
         diagnostics: vec![],
+        fixes: vec![],
     };
 
     rule.check_root(&root, &mut ctx);
+    let root_diagnostics = ctx.diagnostics.len();
+    let mut skipped = vec![];
 
     root.descendants_with_tokens_with(&mut |elem| {
         match elem {
             rslint_parser::NodeOrToken::Node(node) => {
-                if skip_node(directives, &node, rule) || node.kind() == SyntaxKind::ERROR {
+                if skip_node(directives, &node, rule) {
+                    skipped.push(node.as_range());
+                    return false;
+                }
+                if node.kind() == SyntaxKind::ERROR {
                     return false;
                 }
                 rule.check_node(&node, &mut ctx);
@@ -145,7 +182,63 @@ pub fn run_rule(
         };
         true
     });
-    ctx.diagnostics
+
+    // diagnostics of the root are emitted before the walk, so drop the ones inside of nodes it skipped
+    let node_diagnostics = ctx.diagnostics.split_off(root_diagnostics);
+    ctx.diagnostics.retain(|diagnostic| {
+        let primary = diagnostic
+            .labels
+            .iter()
+            .find(|label| label.style == LabelStyle::Primary);
+        match primary {
+            Some(label) => !skipped
+                .iter()
+                .any(|range| range.start <= label.range.start && label.range.end <= range.end),
+            None => true,
+        }
+    });
+    ctx.diagnostics.extend(node_diagnostics);
+    RuleResult {
+        diagnostics: ctx.diagnostics,
+        fixes: ctx.fixes,
+    }
+}
+
+/// Remap the diagnostics of a rule to the level the rule is run at.
+///
+/// Diagnostics which are more severe than the level are lowered to it, and less severe diagnostics are
+/// left untouched, because rules should be able to emit warnings and notes for context without them
+/// being remapped to errors. Every diagnostic is removed if the rule is [`RuleLevel::Off`].
+///
+/// # Examples
+/// ```
+/// use rslint_core::{remap_diagnostics_to_level, DiagnosticBuilder, RuleLevel, Severity};
+///
+/// let mut diagnostics = vec![
+///     DiagnosticBuilder::error(0, "no-empty", "empty block").into(),
+///     DiagnosticBuilder::warning(0, "no-empty", "context").into(),
+/// ];
+/// remap_diagnostics_to_level(&mut diagnostics, RuleLevel::Warning);
+/// assert_eq!(diagnostics[0].severity, Severity::Warning);
+///
+/// remap_diagnostics_to_level(&mut diagnostics, RuleLevel::Info);
+/// assert!(diagnostics.iter().all(|diagnostic| diagnostic.severity == Severity::Note));
+///
+/// remap_diagnostics_to_level(&mut diagnostics, RuleLevel::Off);
+/// assert!(diagnostics.is_empty());
+/// ```
+pub fn remap_diagnostics_to_level(diagnostics: &mut Vec<Diagnostic>, level: RuleLevel) {
+    match level.severity() {
+        None => diagnostics.clear(),
+        Some(Severity::Error) => {}
+        Some(severity) => {
+            for diagnostic in diagnostics.iter_mut() {
+                if diagnostic.severity > severity {
+                    diagnostic.severity = severity;
+                }
+            }
+        }
+    }
 }
 
 /// Get a rule by its kebab-case name.
@@ -158,6 +251,27 @@ pub fn get_rule_by_name(name: &str) -> Option<Box<dyn CstRule>> {
         .map(|rule| clone_box(&**rule))
 }
 
+/// The markdown docs of every builtin rule by name, which are embedded by `build.rs`.
+const RULE_DOCS: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/rule_docs.rs"));
+
+/// Get the markdown docs of a builtin rule by its kebab-case name, the docs are generated by `cargo xtask docgen`.
+///
+/// # Examples
+/// ```
+/// use rslint_core::get_rule_docs;
+///
+/// assert!(get_rule_docs("no-empty").unwrap().contains("# no-empty"));
+/// ```
+pub fn get_rule_docs(name: &str) -> Option<&'static str> {
+    RULE_DOCS
+        .iter()
+        .find(|(rule, _)| *rule == name)
+        .map(|(_, docs)| *docs)
+}
+
+/// The names of every group of built in rules.
+pub const GROUP_NAMES: [&str; 1] = ["errors"];
+
 /// Get a group's rules by the group name.
 // TODO: there should be a good way to not have to hardcode all of this
 pub fn get_group_rules_by_name(group_name: &str) -> Option<Vec<Box<dyn CstRule>>> {
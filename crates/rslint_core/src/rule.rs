@@ -3,7 +3,10 @@
 
 #![allow(unused_variables, unused_imports)]
 
-use crate::{Diagnostic, DiagnosticBuilder};
+use crate::{
+    autofix::Fixer, cfg::ControlFlow, globals::Globals, scope::ScopeAnalysis, Diagnostic,
+    DiagnosticBuilder,
+};
 use codespan_reporting::diagnostic::Severity;
 use dyn_clone::DynClone;
 use rslint_parser::{SyntaxNode, SyntaxNodeExt, SyntaxToken};
@@ -79,48 +82,288 @@ pub trait Rule: Debug + DynClone + Send + Sync {
     fn name(&self) -> &'static str;
     /// The name of the group this rule belongs to.
     fn group(&self) -> &'static str;
+    /// A one line description of the rule, which is the first line of its doc comment.
+    ///
+    /// # Examples
+    /// ```
+    /// use rslint_core::{groups::errors::NoEmpty, Rule};
+    ///
+    /// assert_eq!(NoEmpty::new().description(), "Disallow empty block statements.");
+    /// ```
+    fn description(&self) -> &'static str {
+        ""
+    }
+    /// The user facing markdown docs of the rule, including its config and examples.
+    /// The docs of builtin rules are generated by `cargo xtask docgen` and embedded in the linter.
+    fn docs(&self) -> &'static str {
+        ""
+    }
+    /// Structured metadata of the rule, rules declared with [`declare_lint`] generate it
+    /// from their doc comments, their `#[rule(...)]` attribute, and their config fields.
+    ///
+    /// # Examples
+    /// ```
+    /// use rslint_core::{groups::errors::NoEmpty, Rule};
+    ///
+    /// let meta = NoEmpty::new().meta();
+    /// assert!(meta.recommended);
+    /// assert_eq!(meta.config[0].name, "disallowEmptyFunctions");
+    /// assert_eq!(meta.config[0].ty, "bool");
+    /// ```
+    fn meta(&self) -> RuleMeta {
+        RuleMeta::new(self.name(), self.group(), self.description())
+    }
+}
+
+/// Structured metadata of a rule, which is the single source of truth for listing, documenting,
+/// and configuring rules.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleMeta {
+    /// The unique, kebab-case name of the rule.
+    pub name: &'static str,
+    /// The name of the group the rule belongs to.
+    pub group: &'static str,
+    /// A one line description of the rule.
+    pub description: &'static str,
+    /// The doc comment of the rule without its indentation.
+    pub docs: String,
+    /// Whether the rule is enabled by the `recommended` preset.
+    pub recommended: bool,
+    /// Whether the rule attaches fixes to its diagnostics which `--fix` can apply.
+    pub fixable: bool,
+    /// Whether the rule suggests code to replace the offending code with in its diagnostics.
+    pub suggestions: bool,
+    /// Why the rule is deprecated, if it is deprecated.
+    pub deprecated: Option<&'static str>,
+    /// The name of the rule which replaces this rule, if it is deprecated.
+    pub replaced_by: Option<&'static str>,
+    /// The config fields of the rule.
+    pub config: Vec<ConfigField>,
+}
+
+impl RuleMeta {
+    /// Make metadata for a rule which is not recommended, has no fixes, and has no config.
+    pub fn new(name: &'static str, group: &'static str, docs: &'static str) -> Self {
+        Self {
+            name,
+            group,
+            description: docs
+                .lines()
+                .map(|line| line.trim())
+                .find(|line| !line.is_empty())
+                .unwrap_or_default(),
+            docs: unindent(docs),
+            recommended: false,
+            fixable: false,
+            suggestions: false,
+            deprecated: None,
+            replaced_by: None,
+            config: vec![],
+        }
+    }
+}
+
+/// A config field of a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigField {
+    /// The camelCase name of the field in configs.
+    pub name: String,
+    /// The Rust type of the field, such as `bool` or `Vec<String>`.
+    pub ty: &'static str,
+    /// The doc comment of the field without its indentation.
+    pub docs: String,
+    /// The values the field accepts if its type is an enum of names, such as `["all", "local"]`.
+    pub values: &'static [&'static str],
+}
+
+impl ConfigField {
+    /// Make a config field from the snake_case name of the struct field.
+    pub fn new(field: &str, ty: &'static str, docs: &'static str) -> Self {
+        let mut name = String::with_capacity(field.len());
+        let mut upper = false;
+        for c in field.trim_start_matches("r#").chars() {
+            if c == '_' {
+                upper = !name.is_empty();
+            } else if upper {
+                name.extend(c.to_uppercase());
+                upper = false;
+            } else {
+                name.push(c);
+            }
+        }
+        Self {
+            name,
+            ty,
+            docs: unindent(docs),
+            values: &[],
+        }
+    }
+
+    /// Set the names the field accepts, which are taken from the [`ConfigValues`] of its type.
+    pub fn with_values(mut self, values: &'static [&'static str]) -> Self {
+        self.values = values;
+        self
+    }
+}
+
+/// The names a config field accepts if its type is an enum of names, such as `["all", "local"]`.
+///
+/// Every type of a config field of a rule declared with [`declare_lint`] implements this,
+/// types which are not enums of names accept any value of their type and keep the default of no names.
+///
+/// # Examples
+/// ```
+/// use rslint_core::ConfigValues;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// #[serde(rename_all = "kebab-case")]
+/// enum Quotes {
+///     Single,
+///     Double,
+/// }
+///
+/// impl ConfigValues for Quotes {
+///     const NAMES: &'static [&'static str] = &["single", "double"];
+/// }
+/// ```
+pub trait ConfigValues {
+    const NAMES: &'static [&'static str] = &[];
+}
+
+macro_rules! impl_config_values {
+    ($($ty:ty),* $(,)?) => {
+        $(impl ConfigValues for $ty {})*
+    };
+}
+
+impl_config_values!(
+    bool, char, String, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64,
+);
+
+impl<T> ConfigValues for Vec<T> {}
+
+impl<T: ConfigValues> ConfigValues for Option<T> {
+    const NAMES: &'static [&'static str] = T::NAMES;
+}
+
+/// Remove the common indentation of the lines of a doc comment, as well as leading and trailing blank lines.
+fn unindent(docs: &str) -> String {
+    let indent = docs
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    docs.lines()
+        .map(|line| line.get(indent..).unwrap_or_default().trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
 }
 
 dyn_clone::clone_trait_object!(Rule);
 dyn_clone::clone_trait_object!(CstRule);
 
-/// The level configured for a rule.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// The level configured for a rule, from least to most severe.
+///
+/// Rules which are `Off` are not run, and the diagnostics of `Info` rules are notes, which never fail a run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RuleLevel {
+    Off,
+    Info,
+    #[serde(rename = "warn")]
     Warning,
     Error,
 }
 
+impl RuleLevel {
+    /// The names of the levels as they are written in configs and directives.
+    pub const NAMES: [&'static str; 4] = ["off", "info", "warn", "error"];
+
+    /// The name of this level as it is written in configs and directives.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RuleLevel::Off => "off",
+            RuleLevel::Info => "info",
+            RuleLevel::Warning => "warn",
+            RuleLevel::Error => "error",
+        }
+    }
+
+    /// The most severe severity of the diagnostics of a rule run at this level, `None` if the rule is not run.
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            RuleLevel::Off => None,
+            RuleLevel::Info => Some(Severity::Note),
+            RuleLevel::Warning => Some(Severity::Warning),
+            RuleLevel::Error => Some(Severity::Error),
+        }
+    }
+}
+
+impl std::str::FromStr for RuleLevel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(RuleLevel::Off),
+            "info" => Ok(RuleLevel::Info),
+            "warn" => Ok(RuleLevel::Warning),
+            "error" => Ok(RuleLevel::Error),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Context given to a rule when running it.
 // This is passed by reference and not by Arc, which is very important,
 // Arcs are very expensive to copy, and for 50 rules running on 50 files we will have a total of
 // 2500 copies, which is non ideal at best.
 #[derive(Debug, Clone)]
-pub struct RuleCtx {
+pub struct RuleCtx<'a> {
     /// The file id of the file being linted.
     pub file_id: usize,
     /// Whether the linter is run with the `--verbose` option.
     /// Which dictates whether the linter should include more (potentially spammy) context in diagnostics.
     pub verbose: bool,
+    /// The scope analysis of the file, which is computed once and shared by every rule.
+    pub scope: &'a ScopeAnalysis,
+    /// The control flow graphs of the file, which are computed once and shared by every rule.
+    pub cfg: &'a ControlFlow,
     /// An empty vector of diagnostics which the rule adds to.
     pub diagnostics: Vec<Diagnostic>,
+    /// The fixes attached to diagnostics added by the rule.
+    pub fixes: Vec<Fixer>,
 }
 
-impl RuleCtx {
+impl<'a> RuleCtx<'a> {
+    /// The globals available in the file, merged from the config and any `/* global */` comments.
+    pub fn globals(&self) -> &'a Globals {
+        self.scope.globals()
+    }
+
     /// Make a new diagnostic builder.
     pub fn err(&mut self, code: impl AsRef<str>, message: impl AsRef<str>) -> DiagnosticBuilder {
         DiagnosticBuilder::error(self.file_id, code.as_ref(), message.as_ref())
     }
 
-    pub fn add_err(&mut self, diagnostic: impl Into<Diagnostic>) {
-        self.diagnostics.push(diagnostic.into())
+    /// Add a diagnostic to the rule's diagnostics, as well as its fix if it has one.
+    pub fn add_err(&mut self, diagnostic: impl Into<DiagnosticBuilder>) {
+        let (diagnostic, fix) = diagnostic.into().finish_with_fix();
+        self.diagnostics.push(diagnostic);
+        self.fixes.extend(fix);
     }
 }
 
 /// The result of running a single rule on a syntax tree.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct RuleResult {
     pub diagnostics: Vec<Diagnostic>,
+    /// The fixes offered by the rule, in no particular order.
+    pub fixes: Vec<Fixer>,
 }
 
 impl RuleResult {
@@ -132,6 +375,7 @@ impl RuleResult {
     pub fn merge(self, other: RuleResult) -> RuleResult {
         RuleResult {
             diagnostics: [self.diagnostics, other.diagnostics].concat(),
+            fixes: [self.fixes, other.fixes].concat(),
         }
     }
 }
@@ -188,6 +432,7 @@ impl Outcome {
 /// declare_lint! {
 ///     /// A description of the rule here
 ///     /// This will be used as the doc for the rule struct
+///     #[rule(recommended)]
 ///     RuleName,
 ///     // The name of the group this rule belongs to.
 ///     groupname,
@@ -211,6 +456,15 @@ impl Outcome {
 /// include a `Correct Code Examples`. Do not include a `Config` header, it is autogenerated
 /// from config field docs.
 ///
+/// # Metadata
+///
+/// A `#[rule(...)]` attribute before the rule name sets the [`RuleMeta`] returned by [`Rule::meta`],
+/// the attribute is not added to the struct. It accepts a comma separated list of:
+/// - `recommended`: the rule is enabled by the `recommended` preset.
+/// - `fixable`: the rule attaches fixes to its diagnostics.
+/// - `suggestions`: the rule suggests code to use instead in its diagnostics.
+/// - `deprecated = "reason"` and `replaced_by = "rule-name"`: the rule is deprecated.
+///
 /// # Config
 ///
 /// After the rule code, the macro accepts fields for the struct. Any field which is
@@ -226,7 +480,7 @@ impl Outcome {
 #[macro_export]
 macro_rules! declare_lint {
     (
-        $(#[$outer:meta])*
+        $(#[$($outer:tt)*])*
         // The rule struct name
         $name:ident,
         $group:ident,
@@ -235,7 +489,7 @@ macro_rules! declare_lint {
         $(,
             // Any fields for the rule
             $(
-                $(#[$inner:meta])*
+                $(#[$($inner:tt)*])*
                 $visibility:vis $key:ident : $val:ty
             ),* $(,)?
         )?
@@ -243,16 +497,16 @@ macro_rules! declare_lint {
         use $crate::Rule;
         use serde::{Deserialize, Serialize};
 
-        $(#[$outer])*
-        #[serde(rename_all = "camelCase")]
-        #[derive(Debug, Clone, Deserialize, Serialize)]
-        pub struct $name {
-            $(
+        $crate::__rule_struct! {
+            [] $([$($outer)*])*;
+            $name {
                 $(
-                    $(#[$inner])*
-                    pub $key: $val
-                ),
-            *)?
+                    $(
+                        $(#[$($inner)*])*
+                        pub $key: $val
+                    ),
+                *)?
+            }
         }
 
         impl $name {
@@ -269,6 +523,124 @@ macro_rules! declare_lint {
             fn group(&self) -> &'static str {
                 stringify!($group)
             }
+
+            fn description(&self) -> &'static str {
+                const DOCS: &str = $crate::__rule_docs!([] $([$($outer)*])*);
+                DOCS.lines()
+                    .map(|line| line.trim())
+                    .find(|line| !line.is_empty())
+                    .unwrap_or_default()
+            }
+
+            fn docs(&self) -> &'static str {
+                $crate::get_rule_docs($code).unwrap_or_default()
+            }
+
+            fn meta(&self) -> $crate::RuleMeta {
+                #[allow(unused_mut)]
+                let mut meta = $crate::RuleMeta::new(
+                    $code,
+                    stringify!($group),
+                    $crate::__rule_docs!([] $([$($outer)*])*),
+                );
+                $crate::__rule_flags!(meta $([$($outer)*])*);
+                $($(
+                    $crate::__rule_field!(meta $key ($val) [] $([$($inner)*])*);
+                )*)?
+                meta
+            }
+        }
+    };
+}
+
+/// Declare the struct of a rule with every attribute of its declaration except `#[rule(...)]`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __rule_struct {
+    ([$($attrs:tt)*]; $name:ident { $($fields:tt)* }) => {
+        #[derive(Debug, Clone, Deserialize, Serialize)]
+        $($attrs)*
+        #[serde(rename_all = "camelCase")]
+        pub struct $name {
+            $($fields)*
         }
     };
+    ([$($attrs:tt)*] [rule $($flags:tt)*] $($rest:tt)*) => {
+        $crate::__rule_struct!([$($attrs)*] $($rest)*);
+    };
+    ([$($attrs:tt)*] [$($attr:tt)*] $($rest:tt)*) => {
+        $crate::__rule_struct!([$($attrs)* #[$($attr)*]] $($rest)*);
+    };
+}
+
+/// Set the metadata flags of the `#[rule(...)]` attribute of a rule declaration.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __rule_flags {
+    ($meta:ident) => {};
+    ($meta:ident [rule($($flags:tt)*)] $($rest:tt)*) => {
+        $crate::__rule_flag!($meta $($flags)*);
+        $crate::__rule_flags!($meta $($rest)*);
+    };
+    ($meta:ident [$($attr:tt)*] $($rest:tt)*) => {
+        $crate::__rule_flags!($meta $($rest)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __rule_flag {
+    ($meta:ident) => {};
+    ($meta:ident recommended $(, $($rest:tt)*)?) => {
+        $meta.recommended = true;
+        $crate::__rule_flag!($meta $($($rest)*)?);
+    };
+    ($meta:ident fixable $(, $($rest:tt)*)?) => {
+        $meta.fixable = true;
+        $crate::__rule_flag!($meta $($($rest)*)?);
+    };
+    ($meta:ident suggestions $(, $($rest:tt)*)?) => {
+        $meta.suggestions = true;
+        $crate::__rule_flag!($meta $($($rest)*)?);
+    };
+    ($meta:ident deprecated = $reason:literal $(, $($rest:tt)*)?) => {
+        $meta.deprecated = Some($reason);
+        $crate::__rule_flag!($meta $($($rest)*)?);
+    };
+    ($meta:ident replaced_by = $rule:literal $(, $($rest:tt)*)?) => {
+        $meta.replaced_by = Some($rule);
+        $crate::__rule_flag!($meta $($($rest)*)?);
+    };
+}
+
+/// Add a field of a rule declaration to the config of its metadata, unless serde skips the field.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __rule_field {
+    ($meta:ident $key:ident ($val:ty) [$($attrs:tt)*]) => {
+        $meta.config.push($crate::ConfigField::new(
+            stringify!($key),
+            stringify!($val),
+            $crate::__rule_docs!([] $($attrs)*),
+        ).with_values(<$val as $crate::ConfigValues>::NAMES));
+    };
+    ($meta:ident $key:ident ($val:ty) [$($attrs:tt)*] [serde(skip)] $($rest:tt)*) => {};
+    ($meta:ident $key:ident ($val:ty) [$($attrs:tt)*] [$($attr:tt)*] $($rest:tt)*) => {
+        $crate::__rule_field!($meta $key ($val) [$($attrs)* [$($attr)*]] $($rest)*);
+    };
+}
+
+/// Concatenate the doc attributes of a rule declaration, ignoring any other attributes.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __rule_docs {
+    ([$($docs:literal)*]) => {
+        concat!($($docs, "\n"),*)
+    };
+    ([$($docs:literal)*] [doc = $doc:literal] $($rest:tt)*) => {
+        $crate::__rule_docs!([$($docs)* $doc] $($rest)*)
+    };
+    ([$($docs:literal)*] [$($attr:tt)*] $($rest:tt)*) => {
+        $crate::__rule_docs!([$($docs)*] $($rest)*)
+    };
 }
@@ -2,8 +2,8 @@
 
 #[doc(no_inline)]
 pub use crate::{
-    declare_lint, rule_tests, util, CstRule, Diagnostic, DiagnosticBuilder, Label, Outcome,
-    RuleCtx, RuleResult,
+    declare_lint, rule_tests, util, ConfigValues, ControlFlow, CstRule, Diagnostic,
+    DiagnosticBuilder, Fixer, Label, Outcome, RuleCtx, RuleResult, ScopeAnalysis,
 };
 
 #[doc(no_inline)]
@@ -0,0 +1,452 @@
+//! The tree walk which builds a [`ScopeAnalysis`].
+//!
+//! Declarations are collected in a single walk, then every reference is resolved after the walk,
+//! so hoisted declarations are visible to references which come before them.
+
+use super::*;
+use crate::globals::Globals;
+use rslint_parser::{
+    ast::{AssignExpr, AssignOp, UnaryExpr, VarDecl},
+    SyntaxKind::{self, *},
+    SyntaxNodeExt,
+};
+
+/// How the identifiers inside of a pattern are used.
+#[derive(Debug, Copy, Clone)]
+enum PatternMode {
+    /// The pattern declares bindings, e.g. `let { a } = b`.
+    Declare(BindingKind, TextRange),
+    /// The pattern assigns to existing bindings, e.g. `[a, b] = c`.
+    Assign,
+}
+
+fn is_pattern(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SINGLE_PATTERN
+            | ARRAY_PATTERN
+            | OBJECT_PATTERN
+            | ASSIGN_PATTERN
+            | REST_PATTERN
+            | KEY_VALUE_PATTERN
+    )
+}
+
+pub(super) struct ScopeBuilder {
+    analysis: ScopeAnalysis,
+    stack: Vec<ScopeId>,
+}
+
+impl ScopeBuilder {
+    pub fn new(root: &SyntaxNode) -> Self {
+        let kind = if root.kind() == MODULE {
+            ScopeKind::Module
+        } else {
+            ScopeKind::Global
+        };
+
+        let mut builder = Self {
+            analysis: ScopeAnalysis {
+                scopes: vec![],
+                bindings: vec![],
+                references: vec![],
+                binding_offsets: HashMap::new(),
+                reference_offsets: HashMap::new(),
+                globals: Globals::default(),
+            },
+            stack: vec![],
+        };
+        builder.push_scope(kind, root.text_range());
+        builder
+    }
+
+    pub fn build(mut self, root: &SyntaxNode) -> ScopeAnalysis {
+        self.visit_children(root);
+        self.resolve();
+        self.analysis
+    }
+
+    fn current(&self) -> ScopeId {
+        *self.stack.last().expect("the root scope is never popped")
+    }
+
+    fn push_scope(&mut self, kind: ScopeKind, range: TextRange) {
+        let id = ScopeId(self.analysis.scopes.len());
+        let parent = self.stack.last().copied();
+        if let Some(parent) = parent {
+            self.analysis.scopes[parent.0].children.push(id);
+        }
+        self.analysis.scopes.push(Scope {
+            kind,
+            range,
+            parent,
+            children: vec![],
+            bindings: vec![],
+            references: vec![],
+            names: HashMap::new(),
+        });
+        self.stack.push(id);
+    }
+
+    fn pop_scope(&mut self) {
+        self.stack.pop();
+    }
+
+    fn declare(&mut self, ident: &SyntaxNode, kind: BindingKind, declaration: TextRange) {
+        let scope = if kind == BindingKind::Var {
+            self.analysis.var_scope(self.current())
+        } else {
+            self.current()
+        };
+        self.declare_in(
+            scope,
+            ident.trimmed_text().to_string(),
+            ident.trimmed_range(),
+            kind,
+            declaration,
+        );
+    }
+
+    fn declare_in(
+        &mut self,
+        scope: ScopeId,
+        name: String,
+        range: TextRange,
+        kind: BindingKind,
+        declaration: TextRange,
+    ) {
+        if let Some(&existing) = self.analysis.scopes[scope.0].names.get(&name) {
+            self.analysis.bindings[existing.0]
+                .redeclarations
+                .push(range);
+            self.analysis
+                .binding_offsets
+                .insert(range.start().into(), existing);
+            return;
+        }
+
+        let id = BindingId(self.analysis.bindings.len());
+        if kind != BindingKind::Arguments {
+            self.analysis
+                .binding_offsets
+                .insert(range.start().into(), id);
+        }
+        let scope_data = &mut self.analysis.scopes[scope.0];
+        scope_data.names.insert(name.clone(), id);
+        scope_data.bindings.push(id);
+        self.analysis.bindings.push(Binding {
+            name,
+            kind,
+            range,
+            declaration,
+            scope,
+            redeclarations: vec![],
+            references: vec![],
+        });
+    }
+
+    fn reference(&mut self, ident: &SyntaxNode, kind: ReferenceKind) {
+        let id = ReferenceId(self.analysis.references.len());
+        let range = ident.trimmed_range();
+        let scope = self.current();
+        self.analysis.scopes[scope.0].references.push(id);
+        self.analysis
+            .reference_offsets
+            .insert(range.start().into(), id);
+        self.analysis.references.push(Reference {
+            name: ident.trimmed_text().to_string(),
+            kind,
+            range,
+            scope,
+            binding: None,
+            in_tdz: false,
+        });
+    }
+
+    fn visit_children(&mut self, node: &SyntaxNode) {
+        for child in node.children() {
+            self.visit(&child);
+        }
+    }
+
+    fn visit(&mut self, node: &SyntaxNode) {
+        match node.kind() {
+            NAME_REF => self.visit_name_ref(node),
+            // shorthand properties such as `{ a }`
+            IDENT_PROP => {
+                if let Some(name) = node.child_with_kind(NAME) {
+                    self.reference(&name, ReferenceKind::Read);
+                }
+            }
+            VAR_DECL => self.visit_var_decl(node),
+            FN_DECL => {
+                if let Some(name) = node.child_with_kind(NAME) {
+                    self.declare(&name, BindingKind::Function, node.trimmed_range());
+                }
+                self.visit_function(node);
+            }
+            FN_EXPR | ARROW_EXPR => self.visit_function(node),
+            METHOD | GETTER | SETTER => {
+                // computed keys are evaluated outside of the method
+                if let Some(key) = node.child_with_kind(COMPUTED_PROPERTY_NAME) {
+                    self.visit(&key);
+                }
+                self.visit_function(node);
+            }
+            CLASS_DECL | CLASS_EXPR => self.visit_class(node),
+            BLOCK_STMT => {
+                self.push_scope(ScopeKind::Block, node.trimmed_range());
+                self.visit_children(node);
+                self.pop_scope();
+            }
+            FOR_STMT | FOR_IN_STMT | FOR_OF_STMT => {
+                self.push_scope(ScopeKind::For, node.trimmed_range());
+                for child in node.children() {
+                    if child.kind() == FOR_STMT_INIT && node.kind() != FOR_STMT {
+                        self.visit_for_in_init(&child);
+                    } else {
+                        self.visit(&child);
+                    }
+                }
+                self.pop_scope();
+            }
+            SWITCH_STMT => {
+                // the discriminant is evaluated outside of the scope of the cases
+                if let Some(cond) = node.child_with_kind(CONDITION) {
+                    self.visit(&cond);
+                }
+                self.push_scope(ScopeKind::Switch, node.trimmed_range());
+                for child in node.children().filter(|child| child.kind() != CONDITION) {
+                    self.visit(&child);
+                }
+                self.pop_scope();
+            }
+            CATCH_CLAUSE => {
+                self.push_scope(ScopeKind::Catch, node.trimmed_range());
+                for child in node.children() {
+                    if is_pattern(child.kind()) {
+                        let mode =
+                            PatternMode::Declare(BindingKind::CatchParam, child.trimmed_range());
+                        self.visit_pattern(&child, mode);
+                    } else {
+                        self.visit(&child);
+                    }
+                }
+                self.pop_scope();
+            }
+            ASSIGN_EXPR => {
+                for (idx, child) in node.children().enumerate() {
+                    if idx == 0 && is_pattern(child.kind()) {
+                        self.visit_pattern(&child, PatternMode::Assign);
+                    } else {
+                        self.visit(&child);
+                    }
+                }
+            }
+            IMPORT_DECL => self.visit_import(node),
+            EXPORT_NAMED => {
+                // `export { a } from "b"` does not refer to any local bindings
+                let reexport = node
+                    .children_with_tokens()
+                    .any(|elem| elem.kind() == STRING);
+                if !reexport {
+                    for specifier in node.children().filter(|n| n.kind() == SPECIFIER) {
+                        if let Some(name) = specifier.child_with_kind(NAME) {
+                            self.reference(&name, ReferenceKind::Read);
+                        }
+                    }
+                }
+            }
+            kind if is_pattern(kind) => self.visit_pattern(node, PatternMode::Assign),
+            _ => self.visit_children(node),
+        }
+    }
+
+    fn visit_name_ref(&mut self, node: &SyntaxNode) {
+        let parent = match node.parent() {
+            Some(parent) => parent,
+            None => return self.reference(node, ReferenceKind::Read),
+        };
+
+        let kind = match parent.kind() {
+            // labels are not variables
+            BREAK_STMT | CONTINUE_STMT => return,
+            ASSIGN_EXPR if parent.first_child().as_ref() == Some(node) => {
+                if parent.to::<AssignExpr>().op() == Some(AssignOp::Assign) {
+                    ReferenceKind::Write
+                } else {
+                    ReferenceKind::ReadWrite
+                }
+            }
+            UNARY_EXPR if parent.to::<UnaryExpr>().is_update() => ReferenceKind::ReadWrite,
+            _ => ReferenceKind::Read,
+        };
+        self.reference(node, kind);
+    }
+
+    /// Visit the left hand side of a `for in` or `for of` loop, which is either a declaration or an assignment target.
+    fn visit_for_in_init(&mut self, init: &SyntaxNode) {
+        for child in init.children() {
+            match child.kind() {
+                NAME_REF => self.reference(&child, ReferenceKind::Write),
+                kind if is_pattern(kind) => self.visit_pattern(&child, PatternMode::Assign),
+                _ => self.visit(&child),
+            }
+        }
+    }
+
+    fn visit_var_decl(&mut self, node: &SyntaxNode) {
+        let decl = node.to::<VarDecl>();
+        let kind = if decl.is_const() {
+            BindingKind::Const
+        } else if decl.is_let() {
+            BindingKind::Let
+        } else {
+            BindingKind::Var
+        };
+
+        for declarator in node.children().filter(|n| n.kind() == DECLARATOR) {
+            let mode = PatternMode::Declare(kind, declarator.trimmed_range());
+            for child in declarator.children() {
+                if is_pattern(child.kind()) {
+                    self.visit_pattern(&child, mode);
+                } else {
+                    self.visit(&child);
+                }
+            }
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &SyntaxNode, mode: PatternMode) {
+        if pattern.kind() == SINGLE_PATTERN {
+            if let Some(name) = pattern.child_with_kind(NAME) {
+                match mode {
+                    PatternMode::Declare(kind, declaration) => {
+                        self.declare(&name, kind, declaration)
+                    }
+                    PatternMode::Assign => self.reference(&name, ReferenceKind::Write),
+                }
+            }
+            return;
+        }
+
+        // default values, computed keys, and member expression targets are all visited as normal expressions
+        for child in pattern.children() {
+            match child.kind() {
+                kind if is_pattern(kind) => self.visit_pattern(&child, mode),
+                NAME_REF if matches!(mode, PatternMode::Assign) => {
+                    self.reference(&child, ReferenceKind::Write)
+                }
+                _ => self.visit(&child),
+            }
+        }
+    }
+
+    fn visit_function(&mut self, node: &SyntaxNode) {
+        self.push_scope(ScopeKind::Function, node.trimmed_range());
+        let scope = self.current();
+
+        if node.kind() != ARROW_EXPR {
+            let start = node.trimmed_range().start();
+            self.declare_in(
+                scope,
+                "arguments".to_string(),
+                TextRange::empty(start),
+                BindingKind::Arguments,
+                node.trimmed_range(),
+            );
+        }
+
+        for (idx, child) in node.children().enumerate() {
+            match child.kind() {
+                // function declaration names are declared by the caller
+                NAME if node.kind() == FN_EXPR => {
+                    self.declare(&child, BindingKind::Function, node.trimmed_range())
+                }
+                NAME | COMPUTED_PROPERTY_NAME => {}
+                PARAMETER_LIST => {
+                    for param in child.children() {
+                        let mode = PatternMode::Declare(BindingKind::Param, param.trimmed_range());
+                        self.visit_pattern(&param, mode);
+                    }
+                }
+                // `a => a`
+                NAME_REF if idx == 0 && node.kind() == ARROW_EXPR => {
+                    self.declare(&child, BindingKind::Param, child.trimmed_range())
+                }
+                // the body of a function shares the scope of its parameters
+                BLOCK_STMT => self.visit_children(&child),
+                _ => self.visit(&child),
+            }
+        }
+        self.pop_scope();
+    }
+
+    fn visit_class(&mut self, node: &SyntaxNode) {
+        let name = node.child_with_kind(NAME);
+        if node.kind() == CLASS_DECL {
+            if let Some(name) = &name {
+                self.declare(name, BindingKind::Class, node.trimmed_range());
+            }
+        }
+
+        self.push_scope(ScopeKind::Class, node.trimmed_range());
+        if node.kind() == CLASS_EXPR {
+            if let Some(name) = &name {
+                self.declare(name, BindingKind::Class, node.trimmed_range());
+            }
+        }
+        self.visit_children(node);
+        self.pop_scope();
+    }
+
+    fn visit_import(&mut self, node: &SyntaxNode) {
+        let declaration = node.trimmed_range();
+        for child in node.children() {
+            match child.kind() {
+                // `import a from "b"`
+                NAME => self.declare(&child, BindingKind::Import, declaration),
+                // `import * as a from "b"`
+                WILDCARD_IMPORT => {
+                    if let Some(name) = child.child_with_kind(NAME) {
+                        self.declare(&name, BindingKind::Import, declaration);
+                    }
+                }
+                // `import { a, b as c } from "d"`
+                NAMED_IMPORTS => {
+                    for specifier in child.children().filter(|n| n.kind() == SPECIFIER) {
+                        if let Some(name) = specifier.children().filter(|n| n.kind() == NAME).last()
+                        {
+                            self.declare(&name, BindingKind::Import, declaration);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn resolve(&mut self) {
+        for idx in 0..self.analysis.references.len() {
+            let reference = &self.analysis.references[idx];
+            let binding = match self.analysis.lookup_id(reference.scope, &reference.name) {
+                Some(binding) => binding,
+                None => continue,
+            };
+
+            let binding_data = &self.analysis.bindings[binding.0];
+            // a reference inside of a nested function may be run after the declaration
+            let in_tdz = binding_data.kind.is_lexical()
+                && reference.range.start() < binding_data.declaration.end()
+                && self.analysis.var_scope(reference.scope)
+                    == self.analysis.var_scope(binding_data.scope);
+
+            let reference = &mut self.analysis.references[idx];
+            reference.binding = Some(binding);
+            reference.in_tdz = in_tdz;
+            self.analysis.bindings[binding.0]
+                .references
+                .push(ReferenceId(idx));
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! Scope analysis of a file, which resolves identifier references to the declarations they refer to.
+//!
+//! The analysis is computed once per file by the runner and is shared by every rule through
+//! [`RuleCtx::scope`](crate::RuleCtx::scope). Syntax nodes cannot be shared between threads, therefore
+//! the analysis refers to nodes by their ranges, you can get the binding or reference of a node
+//! using [`ScopeAnalysis::binding_at`] and [`ScopeAnalysis::reference_at`].
+//!
+//! The analysis follows ECMAScript semantics with a couple of simplifications:
+//! - Function declarations inside of blocks are scoped to the block, as if the file was strict.
+//! - Each function has a single scope for its name (if it is an expression), its parameters, and its body.
+
+mod builder;
+
+use crate::globals::Globals;
+use crate::Span;
+use rslint_parser::{SyntaxNode, TextRange};
+use std::collections::HashMap;
+
+/// The id of a scope inside of a [`ScopeAnalysis`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScopeId(pub usize);
+
+/// The id of a binding inside of a [`ScopeAnalysis`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BindingId(pub usize);
+
+/// The id of a reference inside of a [`ScopeAnalysis`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReferenceId(pub usize);
+
+/// The kind of syntax which created a scope.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ScopeKind {
+    /// The root scope of a script.
+    Global,
+    /// The root scope of a module.
+    Module,
+    /// A function, arrow function, method, getter, or setter.
+    Function,
+    /// A block statement.
+    Block,
+    /// The head and body of a `for`, `for in`, or `for of` loop.
+    For,
+    /// The cases of a switch statement.
+    Switch,
+    /// A catch clause, which contains the catch parameter.
+    Catch,
+    /// A class declaration or expression.
+    Class,
+}
+
+impl ScopeKind {
+    /// Whether `var` declarations inside of this scope are declared in this scope.
+    pub fn is_var_scope(self) -> bool {
+        matches!(
+            self,
+            ScopeKind::Global | ScopeKind::Module | ScopeKind::Function
+        )
+    }
+}
+
+/// The kind of declaration which created a binding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BindingKind {
+    Var,
+    Let,
+    Const,
+    Function,
+    Class,
+    Param,
+    CatchParam,
+    Import,
+    /// The implicit `arguments` object of a non-arrow function.
+    Arguments,
+}
+
+impl BindingKind {
+    /// Whether the binding is lexically declared, meaning it cannot be used before its declaration (TDZ).
+    pub fn is_lexical(self) -> bool {
+        matches!(
+            self,
+            BindingKind::Let | BindingKind::Const | BindingKind::Class
+        )
+    }
+
+    /// Whether the binding is hoisted and can be used before its declaration.
+    pub fn is_hoisted(self) -> bool {
+        matches!(self, BindingKind::Var | BindingKind::Function)
+    }
+}
+
+/// The way a reference uses its binding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    /// The value is only read, e.g. `foo()`.
+    Read,
+    /// The value is only written, e.g. `foo = 5`.
+    Write,
+    /// The value is read then written, e.g. `foo += 5` or `foo++`.
+    ReadWrite,
+}
+
+/// A single scope, such as a function or a block.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    /// The range of the node which created the scope.
+    pub range: TextRange,
+    pub parent: Option<ScopeId>,
+    /// The scopes directly nested in this scope, in source order.
+    pub children: Vec<ScopeId>,
+    /// The bindings declared in this scope, in source order.
+    pub bindings: Vec<BindingId>,
+    /// The references which occur directly inside of this scope.
+    pub references: Vec<ReferenceId>,
+    names: HashMap<String, BindingId>,
+}
+
+/// A name declared in a scope.
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub name: String,
+    pub kind: BindingKind,
+    /// The range of the identifier which declared the binding.
+    /// This is an empty range at the start of the function for `arguments`.
+    pub range: TextRange,
+    /// The range of the whole declaration, e.g. the declarator, the function, or the class.
+    pub declaration: TextRange,
+    /// The scope the binding was declared in, `var` and function declarations are hoisted
+    /// to the nearest function, therefore this is not always the innermost scope.
+    pub scope: ScopeId,
+    /// The ranges of the identifiers of any other declarations of the same name in the same scope, e.g. `var a; var a;`.
+    pub redeclarations: Vec<TextRange>,
+    /// Every reference resolved to this binding.
+    pub references: Vec<ReferenceId>,
+}
+
+impl Binding {
+    /// Whether the binding was implicitly declared, such as `arguments`.
+    pub fn is_implicit(&self) -> bool {
+        self.kind == BindingKind::Arguments
+    }
+}
+
+/// A usage of a name.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub name: String,
+    pub kind: ReferenceKind,
+    /// The range of the identifier.
+    pub range: TextRange,
+    /// The innermost scope the reference occurs in.
+    pub scope: ScopeId,
+    /// The binding the reference resolves to, or `None` if the name is not declared in the file,
+    /// meaning it refers to a global.
+    pub binding: Option<BindingId>,
+    /// Whether the reference refers to a lexical binding (`let`, `const`, or `class`) before
+    /// it is initialized in the same function, this is an error at runtime (temporal dead zone).
+    pub in_tdz: bool,
+}
+
+impl Reference {
+    pub fn is_read(&self) -> bool {
+        self.kind != ReferenceKind::Write
+    }
+
+    pub fn is_write(&self) -> bool {
+        self.kind != ReferenceKind::Read
+    }
+}
+
+/// The scopes, bindings, and references of a single file.
+#[derive(Debug, Clone)]
+pub struct ScopeAnalysis {
+    scopes: Vec<Scope>,
+    bindings: Vec<Binding>,
+    references: Vec<Reference>,
+    binding_offsets: HashMap<usize, BindingId>,
+    reference_offsets: HashMap<usize, ReferenceId>,
+    globals: Globals,
+}
+
+impl ScopeAnalysis {
+    /// Analyze the scopes of a `SCRIPT` or `MODULE` root node, with the default [`Globals`].
+    pub fn new(root: &SyntaxNode) -> Self {
+        builder::ScopeBuilder::new(root).build(root)
+    }
+
+    /// Use a different set of globals for the file, such as the globals from the config and global comments.
+    pub fn with_globals(mut self, globals: Globals) -> Self {
+        self.globals = globals;
+        self
+    }
+
+    /// The globals which are available in the file.
+    pub fn globals(&self) -> &Globals {
+        &self.globals
+    }
+
+    /// The root scope of the file, which is either a global or module scope.
+    pub fn root(&self) -> ScopeId {
+        ScopeId(0)
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id.0]
+    }
+
+    pub fn binding(&self, id: BindingId) -> &Binding {
+        &self.bindings[id.0]
+    }
+
+    pub fn reference(&self, id: ReferenceId) -> &Reference {
+        &self.references[id.0]
+    }
+
+    /// All scopes of the file, the root scope is always first.
+    pub fn scopes(&self) -> impl Iterator<Item = (ScopeId, &Scope)> {
+        self.scopes
+            .iter()
+            .enumerate()
+            .map(|(idx, scope)| (ScopeId(idx), scope))
+    }
+
+    /// All bindings declared in the file, in source order.
+    pub fn bindings(&self) -> impl Iterator<Item = (BindingId, &Binding)> {
+        self.bindings
+            .iter()
+            .enumerate()
+            .map(|(idx, binding)| (BindingId(idx), binding))
+    }
+
+    /// All references in the file.
+    pub fn references(&self) -> impl Iterator<Item = (ReferenceId, &Reference)> {
+        self.references
+            .iter()
+            .enumerate()
+            .map(|(idx, reference)| (ReferenceId(idx), reference))
+    }
+
+    /// Get the binding declared by an identifier (a `NAME` node, or a `NAME_REF` for arrow function parameters).
+    pub fn binding_at(&self, span: impl Span) -> Option<&Binding> {
+        self.binding_offsets
+            .get(&span.as_range().start)
+            .map(|id| self.binding(*id))
+    }
+
+    /// Get the reference made by an identifier (a `NAME_REF` node, or a `NAME` for shorthand properties and exports).
+    pub fn reference_at(&self, span: impl Span) -> Option<&Reference> {
+        self.reference_offsets
+            .get(&span.as_range().start)
+            .map(|id| self.reference(*id))
+    }
+
+    /// Get the binding a reference resolves to.
+    pub fn resolve(&self, reference: &Reference) -> Option<&Binding> {
+        reference.binding.map(|id| self.binding(id))
+    }
+
+    /// Get the innermost scope containing a range.
+    pub fn scope_at(&self, span: impl Span) -> ScopeId {
+        let range = span.as_range();
+        let mut cur = self.root();
+        while let Some(child) = self.scope(cur).children.iter().copied().find(|child| {
+            let child_range = self.scope(*child).range;
+            usize::from(child_range.start()) <= range.start
+                && range.end <= usize::from(child_range.end())
+        }) {
+            cur = child;
+        }
+        cur
+    }
+
+    /// An iterator over a scope and all of its ancestor scopes, ending with the root scope.
+    pub fn ancestors(&self, scope: ScopeId) -> impl Iterator<Item = ScopeId> + '_ {
+        std::iter::successors(Some(scope), move |id| self.scope(*id).parent)
+    }
+
+    /// Find the binding a name would resolve to if it was used in a scope.
+    pub fn lookup(&self, scope: ScopeId, name: &str) -> Option<&Binding> {
+        self.lookup_id(scope, name).map(|id| self.binding(id))
+    }
+
+    /// The nearest function or root scope of a scope, which is where `var` declarations are hoisted to.
+    pub fn var_scope(&self, scope: ScopeId) -> ScopeId {
+        self.ancestors(scope)
+            .find(|id| self.scope(*id).kind.is_var_scope())
+            .unwrap_or_else(|| self.root())
+    }
+
+    /// References which do not resolve to any binding in the file, these refer to globals.
+    pub fn unresolved(&self) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(|reference| reference.binding.is_none())
+    }
+
+    /// Unresolved references whose name is not an available global, these will most likely throw at runtime.
+    pub fn undeclared(&self) -> impl Iterator<Item = &Reference> {
+        self.unresolved()
+            .filter(move |reference| !self.globals.is_declared(&reference.name))
+    }
+
+    /// The names of globals which are implicitly created by assigning to an undeclared name, e.g. `foo = 5`.
+    pub fn implicit_globals(&self) -> Vec<&str> {
+        let mut names = self
+            .unresolved()
+            .filter(|reference| reference.is_write())
+            .map(|reference| reference.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    fn lookup_id(&self, scope: ScopeId, name: &str) -> Option<BindingId> {
+        self.ancestors(scope)
+            .find_map(|id| self.scope(id).names.get(name).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rslint_parser::{parse_module, parse_text};
+
+    fn analyze(code: &str) -> ScopeAnalysis {
+        ScopeAnalysis::new(&parse_module(code, 0).syntax())
+    }
+
+    fn reference<'a>(analysis: &'a ScopeAnalysis, code: &str, needle: &str) -> &'a Reference {
+        let start = code.find(needle).expect("needle not in code");
+        analysis
+            .reference_at(start..start + 1)
+            .expect("no reference at needle")
+    }
+
+    fn resolved_kind(analysis: &ScopeAnalysis, code: &str, needle: &str) -> Option<BindingKind> {
+        let reference = reference(analysis, code, needle);
+        analysis.resolve(reference).map(|binding| binding.kind)
+    }
+
+    #[test]
+    fn declarations() {
+        let code = "
+            import def, { a as imp } from 'x';
+            import * as ns from 'y';
+            var v; let l; const c = 1; function f(p, { q }, ...r) {} class C {}
+            try {} catch (e) { e }
+            def; imp; ns; v; l; c; f; C;
+        ";
+        let analysis = analyze(code);
+        let kinds = analysis
+            .bindings()
+            .filter(|(_, binding)| !binding.is_implicit())
+            .map(|(_, binding)| (binding.name.as_str(), binding.kind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                ("def", BindingKind::Import),
+                ("imp", BindingKind::Import),
+                ("ns", BindingKind::Import),
+                ("v", BindingKind::Var),
+                ("l", BindingKind::Let),
+                ("c", BindingKind::Const),
+                ("f", BindingKind::Function),
+                ("p", BindingKind::Param),
+                ("q", BindingKind::Param),
+                ("r", BindingKind::Param),
+                ("C", BindingKind::Class),
+                ("e", BindingKind::CatchParam),
+            ]
+        );
+        assert!(analysis.unresolved().next().is_none());
+        assert_eq!(
+            resolved_kind(&analysis, code, "e }"),
+            Some(BindingKind::CatchParam)
+        );
+    }
+
+    #[test]
+    fn hoisting_and_shadowing() {
+        let code = "
+            foo(); bar;
+            function foo() { { var bar = 1; } let baz; { let baz; baz; } }
+            function arrow() { return x => x; }
+            var x;
+        ";
+        let analysis = analyze(code);
+        let foo = reference(&analysis, code, "foo()");
+        assert_eq!(analysis.resolve(foo).unwrap().kind, BindingKind::Function);
+        assert!(!foo.in_tdz);
+
+        // `bar` is hoisted to the function scope, not the root scope
+        assert!(reference(&analysis, code, "bar;").binding.is_none());
+
+        let inner = reference(&analysis, code, "baz; }");
+        let binding = analysis.resolve(inner).unwrap();
+        assert_eq!(analysis.scope(binding.scope).kind, ScopeKind::Block);
+
+        let param = reference(&analysis, code, "x; }");
+        assert_eq!(analysis.resolve(param).unwrap().kind, BindingKind::Param);
+    }
+
+    #[test]
+    fn temporal_dead_zone() {
+        let code = "
+            a; let a = 5;
+            function f() { a; b; }
+            const b = b;
+            class C extends C {}
+        ";
+        let analysis = analyze(code);
+        let refs = analysis
+            .references()
+            .map(|(_, reference)| (reference.name.as_str(), reference.in_tdz))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            refs,
+            vec![
+                ("a", true),
+                ("a", false),
+                ("b", false),
+                ("b", true),
+                ("C", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn reference_kinds_and_globals() {
+        let code = "
+            let a; a = 1; a += 1; a++; [a, undeclared] = [];
+            for (other in a) {}
+            export { a as b };
+            ({ a });
+            lbl: while (true) { break lbl; }
+            window.foo;
+        ";
+        let analysis = analyze(code);
+        let kinds = analysis
+            .references()
+            .map(|(_, reference)| (reference.name.as_str(), reference.kind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                ("a", ReferenceKind::Write),
+                ("a", ReferenceKind::ReadWrite),
+                ("a", ReferenceKind::ReadWrite),
+                ("a", ReferenceKind::Write),
+                ("undeclared", ReferenceKind::Write),
+                ("other", ReferenceKind::Write),
+                ("a", ReferenceKind::Read),
+                ("a", ReferenceKind::Read),
+                ("a", ReferenceKind::Read),
+                ("window", ReferenceKind::Read),
+            ]
+        );
+        assert_eq!(analysis.implicit_globals(), vec!["other", "undeclared"]);
+    }
+
+    #[test]
+    fn function_scopes() {
+        let code = "
+            const f = function g(a) { g; arguments; };
+            const o = { m(b) { b }, get p() { return arguments } };
+            x => arguments;
+        ";
+        let analysis = analyze(code);
+        assert_eq!(
+            resolved_kind(&analysis, code, "g;"),
+            Some(BindingKind::Function)
+        );
+        assert_eq!(
+            resolved_kind(&analysis, code, "arguments;"),
+            Some(BindingKind::Arguments)
+        );
+        assert_eq!(
+            resolved_kind(&analysis, code, "b }"),
+            Some(BindingKind::Param)
+        );
+        assert_eq!(
+            resolved_kind(&analysis, code, "arguments }"),
+            Some(BindingKind::Arguments)
+        );
+        assert_eq!(resolved_kind(&analysis, code, "arguments;\n"), None);
+
+        let start = code.find("b }").unwrap();
+        let scope = analysis.scope_at(start..start + 1);
+        assert_eq!(analysis.scope(scope).kind, ScopeKind::Function);
+        assert!(analysis.lookup(scope, "g").is_none());
+        assert!(analysis.lookup(scope, "f").is_some());
+    }
+
+    #[test]
+    fn script_root_scope() {
+        let analysis = ScopeAnalysis::new(&parse_text("var a; a;", 0).syntax());
+        assert_eq!(analysis.scope(analysis.root()).kind, ScopeKind::Global);
+        assert_eq!(analysis.scopes().count(), 1);
+    }
+}
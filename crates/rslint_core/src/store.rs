@@ -1,12 +1,17 @@
 //! A rule store, which houses rule groups as well as individual rules.
 
 use crate::groups::*;
-use crate::CstRule;
+use crate::{globals::Globals, CstRule, RuleLevel};
+use std::collections::HashMap;
 
 /// A utility structure for housing CST rules for a linting run.
 #[derive(Debug, Default, Clone)]
 pub struct CstRuleStore {
     pub rules: Vec<Box<dyn CstRule>>,
+    /// The globals available to every file linted with this store, not including `/* global */` comments.
+    pub globals: Globals,
+    /// The level of each rule which is not run as an error.
+    pub levels: HashMap<&'static str, RuleLevel>,
 }
 
 impl CstRuleStore {
@@ -39,4 +44,24 @@ impl CstRuleStore {
             .find(|rule| rule.name() == rule_name.as_ref())
             .cloned()
     }
+
+    /// The level a rule of this store is run at, rules are run as errors unless they have another level.
+    pub fn level(&self, rule_name: impl AsRef<str>) -> RuleLevel {
+        self.levels
+            .get(rule_name.as_ref())
+            .copied()
+            .unwrap_or(RuleLevel::Error)
+    }
+
+    /// Set the level of a rule, rules set to [`RuleLevel::Off`] are removed from the store.
+    pub fn set_level(&mut self, rule_name: &'static str, level: RuleLevel) {
+        if level == RuleLevel::Off {
+            self.rules.retain(|rule| rule.name() != rule_name);
+        }
+        if level == RuleLevel::Error {
+            self.levels.remove(rule_name);
+        } else {
+            self.levels.insert(rule_name, level);
+        }
+    }
 }
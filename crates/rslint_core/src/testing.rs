@@ -52,7 +52,9 @@ macro_rules! rule_tests {
         fn $err_name() {
             $(
                 let res = rslint_parser::parse_module($code, 0);
-                let errs = $crate::run_rule(&$rule, 0, res.syntax(), true, &[]);
+                let scope = $crate::ScopeAnalysis::new(&res.syntax());
+                let cfg = $crate::ControlFlow::new(&res.syntax());
+                let errs = $crate::run_rule(&$rule, 0, res.syntax(), true, &[], &scope, &cfg).diagnostics;
                 if errs.is_empty() {
                     panic!("\nExpected:\n```\n{}\n```\nto fail linting, but instead it passed (with {} parsing errors)", $code, res.errors().len());
                 }
@@ -63,7 +65,9 @@ macro_rules! rule_tests {
         fn $ok_name() {
             $(
                 let res = rslint_parser::parse_module($ok_code, 0);
-                let errs = $crate::run_rule(&$rule, 0, res.syntax(), true, &[]);
+                let scope = $crate::ScopeAnalysis::new(&res.syntax());
+                let cfg = $crate::ControlFlow::new(&res.syntax());
+                let errs = $crate::run_rule(&$rule, 0, res.syntax(), true, &[], &scope, &cfg).diagnostics;
 
                 if !errs.is_empty() {
                     panic!("\nExpected:\n```\n{}\n```\nto pass linting, but instead it threw errors (along with {} parsing errors):\n\n", $ok_code, res.errors().len());
@@ -547,3 +547,42 @@ pub fn outer_function(node: impl Borrow<SyntaxNode>) -> Option<SyntaxNode> {
         .skip(1)
         .find(|ancestor| matches!(ancestor.kind(), ARROW_EXPR | FN_DECL | FN_EXPR))
 }
+
+/// A regular expression in a rule config, which is compiled once when the config is loaded
+/// so an invalid pattern is reported as a config error.
+#[derive(Debug, Clone)]
+pub struct Pattern(regex::Regex);
+
+impl Pattern {
+    /// Compile a pattern, returning an error if it is not a valid regular expression.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        regex::Regex::new(pattern).map(Pattern)
+    }
+
+    /// Whether the pattern matches anywhere in a string.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+
+    /// The source of the pattern.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl ConfigValues for Pattern {}
+
+impl serde::Serialize for Pattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Pattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = std::string::String::deserialize(deserializer)?;
+        Pattern::new(&pattern).map_err(|error| {
+            serde::de::Error::custom(format!("invalid regular expression: {}", error))
+        })
+    }
+}
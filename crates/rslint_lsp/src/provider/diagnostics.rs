@@ -2,14 +2,27 @@
 
 use crate::core::{document::Document, session::Session};
 use codespan_lsp::byte_span_to_range;
-use codespan_reporting::diagnostic::LabelStyle;
+use codespan_reporting::diagnostic::{LabelStyle, Severity};
 use itertools::Itertools;
 use rayon::prelude::*;
-use rslint_core::{apply_top_level_directives, run_rule, DirectiveParser};
+use rslint_core::{
+    apply_top_level_directives, remap_diagnostics_to_level, run_rule, ControlFlow, DirectiveParser,
+    ScopeAnalysis,
+};
 use rslint_parser::SyntaxNode;
 use std::{collections::HashMap, sync::Arc};
 use tower_lsp::lsp_types::*;
 
+/// The LSP severity of a diagnostic, info rules are reported as information.
+fn severity(severity: Severity) -> DiagnosticSeverity {
+    match severity {
+        Severity::Bug | Severity::Error => DiagnosticSeverity::Error,
+        Severity::Warning => DiagnosticSeverity::Warning,
+        Severity::Note => DiagnosticSeverity::Information,
+        Severity::Help => DiagnosticSeverity::Hint,
+    }
+}
+
 fn process_diagnostics(
     document: &Document,
     uri: Url,
@@ -63,7 +76,7 @@ fn process_diagnostics(
                     range => range,
                 }?;
 
-            let severity = Default::default();
+            let severity = Some(severity(diagnostic.severity));
             let code = if let Some(rule) = rule {
                 Some(NumberOrString::String(rule.into()))
             } else {
@@ -120,15 +133,19 @@ pub async fn publish_diagnostics(session: Arc<Session>, uri: Url) -> anyhow::Res
             );
 
             let verbose = false;
+            let root = SyntaxNode::new_root(document.parse.green());
+            let scope = ScopeAnalysis::new(&root).with_globals(new_store.globals.clone());
+            let cfg = ControlFlow::new(&root);
             let rule_diagnostics: HashMap<&str, Vec<rslint_core::Diagnostic>> = new_store
                 .rules
                 .par_iter()
                 .map(|rule| {
                     let root = SyntaxNode::new_root(document.parse.green());
-                    (
-                        rule.name(),
-                        run_rule(&**rule, file_id, root, verbose, &directives),
-                    )
+                    let mut diagnostics =
+                        run_rule(&**rule, file_id, root, verbose, &directives, &scope, &cfg)
+                            .diagnostics;
+                    remap_diagnostics_to_level(&mut diagnostics, new_store.level(rule.name()));
+                    (rule.name(), diagnostics)
                 })
                 .collect();
 
@@ -83,48 +83,22 @@ pub struct RuleFile {
     pub tests: Option<RuleTests>,
 }
 
-/// A single `declare_lint!` declaration.
+/// A single `declare_lint!` declaration, the docs and config of the rule are taken from its [`RuleMeta`](rslint_core::RuleMeta).
 #[derive(Clone)]
 pub struct LintDeclaration {
     pub name: String,
-    pub docstring: Option<String>,
-    pub config_fields: Vec<ConfigField>,
-}
-
-#[derive(Clone)]
-pub struct ConfigField {
-    pub docstring: Option<String>,
-    pub field: Field,
 }
 
 impl Parse for LintDeclaration {
     fn parse(input: ParseStream) -> Result<Self> {
-        let docstring = parse_docstring(input);
+        parse_docstring(input);
         input.parse::<Ident>()?;
         input.parse::<Token!(,)>()?;
         input.parse::<Ident>()?;
         input.parse::<Token!(,)>()?;
         let name = input.parse::<LitStr>()?.value();
-        let _ = input.parse::<Token!(,)>();
-
-        let config_fields = Punctuated::<ConfigField, Token![,]>::parse_terminated(input)?
-            .into_iter()
-            .filter(|field| matches!(field.field.vis, Visibility::Public(_)))
-            .collect();
-
-        Ok(Self {
-            name,
-            docstring,
-            config_fields,
-        })
-    }
-}
-
-impl Parse for ConfigField {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let docstring = parse_docstring(input);
-        let field = input.call(Field::parse_named)?;
-        Ok(Self { docstring, field })
+        let _ = input.parse::<TokenStream>();
+        Ok(Self { name })
     }
 }
 
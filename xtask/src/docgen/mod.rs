@@ -3,7 +3,7 @@ mod extract;
 use crate::project_root;
 use convert_case::{Case, Casing};
 use extract::*;
-use quote::ToTokens;
+use rslint_core::{get_rule_by_name, RuleMeta};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs::{read_dir, read_to_string, write};
@@ -123,8 +123,7 @@ pub fn group_markdown(data: &[(String, RuleFile)], group: &Group) -> String {
             "| [{}](./{}.md) | {} |\n",
             user_facing_name,
             user_facing_name,
-            first_sentence(&rule.lint_declaration.docstring.clone().unwrap_or_default())
-                .unwrap_or_default()
+            rule_meta(rule).description
         ));
     }
     ret.push_str(&format!(
@@ -141,8 +140,11 @@ pub fn rule_src(group_name: &str, rule_name: &str) -> String {
     )
 }
 
-pub fn first_sentence(string: &str) -> Option<&str> {
-    string.trim().split('\n').next().map(|x| x.trim())
+/// The metadata of a builtin rule, which is the source of its docs and config.
+pub fn rule_meta(rule: &RuleFile) -> RuleMeta {
+    get_rule_by_name(&rule.lint_declaration.name)
+        .expect("declare_lint! declaration of a rule which is not in a group")
+        .meta()
 }
 
 pub fn extract_group(group_name: &str) -> Result<HashMap<String, RuleFile>, Box<dyn Error>> {
@@ -157,11 +159,9 @@ pub fn extract_group(group_name: &str) -> Result<HashMap<String, RuleFile>, Box<
 }
 
 pub fn rule_markdown(rule: RuleFile, group: &Group) -> String {
-    let mut ret = rule
-        .lint_declaration
-        .docstring
-        .unwrap_or_default()
-        .replace("```ignore", "```js");
+    let meta = rule_meta(&rule);
+    let mut ret = meta.docs.replace("```ignore", "```js");
+    ret.push('\n');
     ret.insert_str(
         0,
         &format!(
@@ -170,27 +170,28 @@ pub fn rule_markdown(rule: RuleFile, group: &Group) -> String {
         ),
     );
 
-    if !rule.lint_declaration.config_fields.is_empty() {
+    if !meta.config.is_empty() {
         ret.push_str("\n## Config\n");
         ret.push_str("| Name | Type | Description |\n");
         ret.push_str("| ---- | ---- | ----------- |\n");
 
-        for config in rule.lint_declaration.config_fields.iter() {
-            ret.push_str(&format!(
-                "| `{}` | {} | {} |\n",
-                config
-                    .field
-                    .ident
-                    .as_ref()
-                    .unwrap()
-                    .to_string()
-                    .to_case(Case::Camel),
-                config.field.ty.to_token_stream().to_string(),
+        for config in meta.config.iter() {
+            // enums are shown as the names they accept since their Rust type means nothing in a config
+            let ty = if config.values.is_empty() {
+                config.ty.to_string()
+            } else {
                 config
-                    .docstring
-                    .clone()
-                    .unwrap_or_default()
-                    .replace("\n", "<br>")
+                    .values
+                    .iter()
+                    .map(|value| format!("\"{}\"", value))
+                    .collect::<Vec<_>>()
+                    .join(" \\| ")
+            };
+            ret.push_str(&format!(
+                "| `{}` | `{}` | {} |\n",
+                config.name,
+                ty,
+                config.docs.replace("\n", "<br>")
             ));
         }
     }
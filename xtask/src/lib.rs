@@ -4,6 +4,7 @@ pub mod ast;
 pub mod codegen;
 pub mod docgen;
 pub mod glue;
+pub mod schema;
 
 use std::{
     env,
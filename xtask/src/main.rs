@@ -3,7 +3,7 @@ use xtask::{
     codegen::{self, Mode},
     docgen,
     glue::pushd,
-    project_root, run_rustfmt, Result,
+    project_root, run_rustfmt, schema, Result,
 };
 
 fn main() -> Result<()> {
@@ -32,6 +32,10 @@ fn main() -> Result<()> {
             docgen::run();
             Ok(())
         }
+        "schema" => {
+            args.finish()?;
+            schema::run()
+        }
         _ => {
             eprintln!(
                 "\
@@ -43,7 +47,8 @@ SUBCOMMANDS:
     format
     codegen
     syntax
-    docgen"
+    docgen
+    schema"
             );
             Ok(())
         }
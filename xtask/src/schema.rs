@@ -0,0 +1,12 @@
+//! Generation of the JSON Schema of `rslintrc.toml`, which editors use to complete and validate configs.
+
+use crate::{project_root, Result};
+use std::fs::write;
+
+pub const SCHEMA_PATH: &str = "docs/rslintrc.schema.json";
+
+pub fn run() -> Result<()> {
+    let schema = serde_json::to_string_pretty(&rslint_cli::config_schema())?;
+    write(project_root().join(SCHEMA_PATH), schema + "\n")?;
+    Ok(())
+}